@@ -2,38 +2,51 @@ use itertools::Itertools;
 
 advent_of_code::solution!(2);
 
-fn is_valid_id(id: u64) -> bool {
-    let number_of_digit = id.ilog10() + 1;
-    if number_of_digit % 2 == 1 {
-        return true;
+/// Sum of the `digits`-long numbers in `low..=high` that are a `period`-long
+/// block repeated, e.g. `123123` for 6 digits and a period of 3.
+///
+/// Such numbers are `block * 1..01..01` (with `digits / period` ones), so the
+/// ones in range form an arithmetic series over the blocks.
+fn sum_repeats(low: u64, high: u64, digits: u32, period: u32) -> u64 {
+    debug_assert!(digits.is_multiple_of(period));
+    let multiplier = (10u64.pow(digits) - 1) / (10u64.pow(period) - 1);
+    let first = 10u64.pow(period - 1).max(low.div_ceil(multiplier));
+    let last = (10u64.pow(period) - 1).min(high / multiplier);
+    if first > last {
+        return 0;
     }
-    let split_size = 10u64.pow(number_of_digit >> 1);
-    let (high, low) = (id / split_size, id % split_size);
-    high != low
+    multiplier * (first + last) * (last - first + 1) / 2
 }
 
-fn is_valid_id_full(id: u64) -> bool {
-    let number_of_digit = id.ilog10() + 1;
-    for size in 1..number_of_digit {
-        let size = size + 1;
-        if !number_of_digit.is_multiple_of(size) {
-            continue;
-        }
-        let split_size = 10u64.pow(number_of_digit / size);
-        let pattern = id % split_size;
-        let mut remain: u64 = id / split_size;
-        while remain > 0 && remain % split_size == pattern {
-            remain /= split_size
-        }
-        // if all fragments were identical, it is a fake id
-        if remain == 0 {
-            return false;
-        };
+/// Invalid ids are made of a block repeated exactly twice.
+fn sum_doubled(low: u64, high: u64, digits: u32) -> u64 {
+    if digits % 2 == 1 {
+        return 0;
+    }
+    sum_repeats(low, high, digits, digits / 2)
+}
+
+/// Invalid ids are made of a block repeated at least twice.
+///
+/// A number repeating a block of length `p` also repeats blocks of every
+/// multiple of its smallest period, so sums are first restricted to numbers
+/// whose *smallest* period is `p` (inclusion–exclusion over divisors).
+fn sum_repeated(low: u64, high: u64, digits: u32) -> u64 {
+    let periods: Vec<u32> = (1..digits).filter(|p| digits.is_multiple_of(*p)).collect();
+    let mut smallest_period_sums: Vec<u64> = Vec::with_capacity(periods.len());
+    for (i, period) in periods.iter().enumerate() {
+        let with_smaller_period: u64 = periods[..i]
+            .iter()
+            .zip(&smallest_period_sums)
+            .filter(|(p, _)| period.is_multiple_of(**p))
+            .map(|(_, sum)| sum)
+            .sum();
+        smallest_period_sums.push(sum_repeats(low, high, digits, *period) - with_smaller_period);
     }
-    true
+    smallest_period_sums.iter().sum()
 }
 
-fn sum_invalids(input: &str, is_valid: fn(u64) -> bool) -> u64 {
+fn sum_invalids(input: &str, sum_invalids_by_digits: fn(u64, u64, u32) -> u64) -> u64 {
     input
         .trim()
         .split(',')
@@ -42,22 +55,76 @@ fn sum_invalids(input: &str, is_valid: fn(u64) -> bool) -> u64 {
                 .map(|d| d.parse::<u64>().unwrap())
                 .collect_tuple::<(u64, u64)>()
         })
-        .map(|(low, high)| (low..=high).filter(|i| !is_valid(*i)).sum::<u64>())
+        .map(|(low, high)| {
+            // split the range by number of digits
+            (low.max(1).ilog10() + 1..=high.max(1).ilog10() + 1)
+                .map(|digits| {
+                    let low = low.max(10u64.pow(digits - 1));
+                    let high = high.min(10u64.pow(digits) - 1);
+                    sum_invalids_by_digits(low, high, digits)
+                })
+                .sum::<u64>()
+        })
         .sum()
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    Some(sum_invalids(input, is_valid_id))
+    Some(sum_invalids(input, sum_doubled))
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    Some(sum_invalids(input, is_valid_id_full))
+    Some(sum_invalids(input, sum_repeated))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn is_valid_id(id: u64) -> bool {
+        let number_of_digit = id.ilog10() + 1;
+        if number_of_digit % 2 == 1 {
+            return true;
+        }
+        let split_size = 10u64.pow(number_of_digit >> 1);
+        let (high, low) = (id / split_size, id % split_size);
+        high != low
+    }
+
+    fn is_valid_id_full(id: u64) -> bool {
+        let number_of_digit = id.ilog10() + 1;
+        for size in 1..number_of_digit {
+            let size = size + 1;
+            if !number_of_digit.is_multiple_of(size) {
+                continue;
+            }
+            let split_size = 10u64.pow(number_of_digit / size);
+            let pattern = id % split_size;
+            let mut remain: u64 = id / split_size;
+            while remain > 0 && remain % split_size == pattern {
+                remain /= split_size
+            }
+            // if all fragments were identical, it is a fake id
+            if remain == 0 {
+                return false;
+            };
+        }
+        true
+    }
+
+    /// The original brute force, walking every id of every range.
+    fn brute_force(input: &str, is_valid: fn(u64) -> bool) -> u64 {
+        input
+            .trim()
+            .split(',')
+            .filter_map(|rg| {
+                rg.split('-')
+                    .map(|d| d.parse::<u64>().unwrap())
+                    .collect_tuple::<(u64, u64)>()
+            })
+            .map(|(low, high)| (low..=high).filter(|i| !is_valid(*i)).sum::<u64>())
+            .sum()
+    }
+
     #[test]
     fn test_part_one() {
         assert_eq!(part_one("11-22"), Some(33));
@@ -72,4 +139,17 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(4174379265));
     }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for input in [
+            "1-1200000",
+            "95-115,998-1012,9999-10001",
+            "123123-123124,565653-565659,1111111-1111112",
+            "99999999-100000001,2121212118-2121212124",
+        ] {
+            assert_eq!(part_one(input), Some(brute_force(input, is_valid_id)));
+            assert_eq!(part_two(input), Some(brute_force(input, is_valid_id_full)));
+        }
+    }
 }
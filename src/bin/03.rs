@@ -1,71 +1,46 @@
-use std::cmp::max;
-
 advent_of_code::solution!(3);
 
-fn get_bank_voltage(bank: &str) -> u64 {
-    let volts: (Option<u64>, Option<u64>) =
-        bank.chars()
-            .filter_map(|c| c.to_digit(10))
-            .fold((None, None), |acc, d| {
-                let d = d as u64;
-                match acc {
-                    (None, None) => (Some(d), None),
-                    (Some(first), None) => (Some(first), Some(d)),
-                    (Some(first), Some(second)) => {
-                        let r1 = 10 * first + second;
-                        let r2 = 10 * first + d;
-                        let r3 = 10 * second + d;
-                        match max(max(r1, r2), r3) {
-                            v if v == r1 => (Some(first), Some(second)),
-                            v if v == r2 => (Some(first), Some(d)),
-                            v if v == r3 => (Some(second), Some(d)),
-                            _ => unreachable!(),
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            });
-    volts.0.unwrap() * 10 + volts.1.unwrap()
-}
+/// Picks the `k` batteries of `bank` giving the largest joltage, keeping their
+/// order, i.e. the lexicographically largest subsequence of length `k`.
+///
+/// Returns the indexes of the chosen batteries and the resulting joltage.
+/// Banks with less than `k` batteries use all of them.
+fn best_batteries(bank: &[u8], k: usize) -> (Vec<usize>, u64) {
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+    for (i, d) in bank.iter().enumerate() {
+        // drop smaller batteries while enough remain to still pick `k` of them.
+        while let Some(last) = chosen.last()
+            && bank[*last] < *d
+            && chosen.len() + bank.len() - i > k
+        {
+            chosen.pop();
+        }
+        if chosen.len() < k {
+            chosen.push(i);
+        }
+    }
 
-fn joltage(batteries: &[u32]) -> u64 {
-    batteries.iter().fold(0, |acc, b| acc * 10 + *b as u64)
+    let joltage = chosen.iter().fold(0, |acc, i| acc * 10 + bank[*i] as u64);
+    (chosen, joltage)
 }
-fn get_bank_voltage_n(bank: &str, size: usize) -> u64 {
-    let volts: Vec<u32> = bank
+
+fn get_bank_voltage(bank: &str, k: usize) -> u64 {
+    let batteries: Vec<u8> = bank
         .chars()
         .filter_map(|c| c.to_digit(10))
-        .fold(vec![], |mut acc, d| {
-            if acc.len() < size {
-                acc.push(d);
-                return acc;
-            }
-
-            let mut max_joltage = joltage(&acc);
-            let mut max_acc = acc.clone();
-
-            for i in 0..size {
-                let mut new_acc: Vec<u32> = acc
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(j, c)| if i != j { Some(*c) } else { None })
-                    .collect();
-                new_acc.push(d);
-
-                let new_joltage = joltage(&new_acc);
-                if new_joltage > max_joltage {
-                    max_joltage = new_joltage;
-                    max_acc = new_acc;
-                }
-            }
-            max_acc
-        });
-
-    assert_eq!(volts.len(), 12);
-    joltage(&volts)
+        .map(|d| d as u8)
+        .collect();
+    best_batteries(&batteries, k).1
 }
+
 pub fn part_one(input: &str) -> Option<u64> {
-    Some(input.trim().lines().map(get_bank_voltage).sum())
+    Some(
+        input
+            .trim()
+            .lines()
+            .map(|bank| get_bank_voltage(bank, 2))
+            .sum(),
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
@@ -73,7 +48,7 @@ pub fn part_two(input: &str) -> Option<u64> {
         input
             .trim()
             .lines()
-            .map(|bank| get_bank_voltage_n(bank, 12))
+            .map(|bank| get_bank_voltage(bank, 12))
             .sum(),
     )
 }
@@ -88,6 +63,16 @@ mod tests {
         assert_eq!(result, Some(357));
     }
 
+    #[test]
+    fn test_best_batteries() {
+        assert_eq!(
+            best_batteries(&[8, 1, 8, 1, 9, 1, 1], 3),
+            (vec![4, 5, 6], 911)
+        );
+        assert_eq!(best_batteries(&[1, 9, 2, 8], 2), (vec![1, 3], 98));
+        assert_eq!(best_batteries(&[1, 2], 3), (vec![0, 1], 12));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
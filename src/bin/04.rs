@@ -1,64 +1,125 @@
-use std::collections::HashSet;
-
 advent_of_code::solution!(4);
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-fn count_adjacent_rolls(p: &Point, rolls: &HashSet<Point>) -> usize {
-    let minx = if p.x == 0 { 0 } else { p.x - 1 };
-    let miny = if p.y == 0 { 0 } else { p.y - 1 };
-    (minx..p.x + 2)
-        .flat_map(move |x| (miny..p.y + 2).map(move |y| (x, y)))
-        .filter(|(x, y)| (*x != p.x || *y != p.y) && rolls.contains(&Point { x: *x, y: *y }))
-        .count()
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Rolls stored as a dense bitset, with a one cell empty border so that
+/// neighbours never need bounds checks.
+struct Grid {
+    width: usize,
+    rolls: Vec<u64>,
 }
 
-fn read_rolls(input: &str) -> HashSet<Point> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(move |(y, line)| {
-            line.chars().enumerate().filter_map(
-                move |(x, c)| {
-                    if c == '@' { Some(Point { x, y }) } else { None }
-                },
-            )
+impl Grid {
+    fn contains(&self, idx: usize) -> bool {
+        self.rolls[idx / WORD_BITS] >> (idx % WORD_BITS) & 1 == 1
+    }
+
+    fn insert(&mut self, idx: usize) {
+        self.rolls[idx / WORD_BITS] |= 1 << (idx % WORD_BITS);
+    }
+
+    fn remove(&mut self, idx: usize) {
+        self.rolls[idx / WORD_BITS] &= !(1 << (idx % WORD_BITS));
+    }
+
+    fn len(&self) -> usize {
+        self.rolls.len() * WORD_BITS
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rolls.iter().enumerate().flat_map(|(w, word)| {
+            (0..WORD_BITS)
+                .filter(move |b| word >> b & 1 == 1)
+                .map(move |b| w * WORD_BITS + b)
         })
-        .collect()
-}
-pub fn part_one(input: &str) -> Option<u64> {
-    let rolls = read_rolls(input);
-    Some(
-        rolls
-            .iter()
-            .filter(|p| count_adjacent_rolls(p, &rolls) < 4)
-            .count() as u64,
-    )
+    }
+
+    fn neighbours(&self, idx: usize) -> [usize; 8] {
+        let w = self.width;
+        [
+            idx - w - 1,
+            idx - w,
+            idx - w + 1,
+            idx - 1,
+            idx + 1,
+            idx + w - 1,
+            idx + w,
+            idx + w + 1,
+        ]
+    }
+
+    /// Number of adjacent rolls, for every cell.
+    fn neighbour_counts(&self) -> Vec<u8> {
+        let mut counts = vec![0u8; self.len()];
+        for idx in self.iter() {
+            for n in self.neighbours(idx) {
+                counts[n] += 1;
+            }
+        }
+        counts
+    }
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut rolls = read_rolls(input);
-    let mut all_moved_count = 0;
-    loop {
-        let moved: HashSet<Point> = rolls
-            .iter()
-            .filter(|p| count_adjacent_rolls(p, &rolls) < 4)
-            .copied()
-            .collect();
-        if moved.is_empty() {
-            break;
+fn read_rolls(input: &str) -> Grid {
+    let width = input.lines().map(str::len).max().unwrap_or(0) + 2;
+    let height = input.lines().count() + 2;
+    let mut grid = Grid {
+        width,
+        rolls: vec![0; (width * height).div_ceil(WORD_BITS)],
+    };
+    for (y, line) in input.lines().enumerate() {
+        for (x, _) in line.chars().enumerate().filter(|(_, c)| *c == '@') {
+            grid.insert((y + 1) * width + x + 1);
         }
-        all_moved_count += moved.len() as u64;
-        // rolls = rolls.difference(&moved).copied().collect();
-        for m in &moved {
-            rolls.remove(m);
+    }
+    grid
+}
+
+/// Removes accessible rolls (less than 4 neighbours) round after round, until
+/// none is left accessible.
+///
+/// Returns the removal round (starting at 1) of every removed roll. Only the
+/// neighbours of removed rolls are re-checked, from a worklist.
+fn peel(grid: &mut Grid) -> Vec<(usize, u32)> {
+    let mut counts = grid.neighbour_counts();
+    let mut removed = Vec::new();
+
+    let mut queued = vec![false; grid.len()];
+    let mut worklist: Vec<usize> = grid.iter().filter(|idx| counts[*idx] < 4).collect();
+    for idx in &worklist {
+        queued[*idx] = true;
+    }
+
+    let mut round = 0;
+    while !worklist.is_empty() {
+        round += 1;
+        let mut next = Vec::new();
+        for idx in worklist {
+            grid.remove(idx);
+            removed.push((idx, round));
+            for n in grid.neighbours(idx) {
+                counts[n] -= 1;
+                if counts[n] < 4 && grid.contains(n) && !queued[n] {
+                    queued[n] = true;
+                    next.push(n);
+                }
+            }
         }
+        worklist = next;
     }
 
-    Some(all_moved_count)
+    removed
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let grid = read_rolls(input);
+    let counts = grid.neighbour_counts();
+    Some(grid.iter().filter(|idx| counts[*idx] < 4).count() as u64)
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    let mut grid = read_rolls(input);
+    Some(peel(&mut grid).len() as u64)
 }
 
 #[cfg(test)]
@@ -71,6 +132,16 @@ mod tests {
         assert_eq!(result, Some(13));
     }
 
+    #[test]
+    fn test_peel_rounds() {
+        let mut grid = read_rolls(&advent_of_code::template::read_file("examples", DAY));
+        let removed = peel(&mut grid);
+        let first_round = removed.iter().filter(|(_, round)| *round == 1).count();
+        assert_eq!(first_round, 13);
+        assert!(removed.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(grid.iter().count(), 71 - 43);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
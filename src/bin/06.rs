@@ -1,3 +1,6 @@
+use advent_of_code::text_layout::TextBlock;
+use std::fmt::{Display, Formatter};
+
 advent_of_code::solution!(6);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -6,11 +9,36 @@ enum Operation {
     Multiply,
 }
 
-struct Problem<'p> {
-    vals: &'p Vec<u64>,
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum WorksheetError {
+    MissingOperators,
+    UnknownOperator(String),
+    InvalidNumber(String),
+}
+impl Display for WorksheetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorksheetError::MissingOperators => write!(f, "worksheet has no operator line"),
+            WorksheetError::UnknownOperator(op) => write!(f, "unknown operator '{op}'"),
+            WorksheetError::InvalidNumber(n) => write!(f, "invalid number '{n}'"),
+        }
+    }
+}
+
+/// How the numbers of a problem are written.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Reading {
+    /// One number per line.
+    Rows,
+    /// One number per column, digits from top to bottom.
+    Columns,
+}
+
+struct Problem {
+    vals: Vec<u64>,
     op: Operation,
 }
-impl Problem<'_> {
+impl Problem {
     fn solve(&self) -> u64 {
         match self.op {
             Operation::Add => self.vals.iter().sum(),
@@ -18,82 +46,55 @@ impl Problem<'_> {
         }
     }
 }
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut vals: Vec<Vec<u64>> = vec![];
-    let mut opers: Vec<Operation> = vec![];
 
-    for line in input.lines() {
-        for (i, w) in line.split_whitespace().enumerate() {
-            match w {
-                "+" => {
-                    opers.push(Operation::Add);
-                }
-                "*" => {
-                    opers.push(Operation::Multiply);
-                }
-                val => {
-                    if i >= vals.len() {
-                        vals.push(vec![])
-                    };
-                    vals[i].push(val.parse().unwrap());
-                }
-            }
-        }
-    }
+/// Problems are column groups of the worksheet; the last line holds their operators.
+fn read_worksheet(input: &str, reading: Reading) -> Result<Vec<Problem>, WorksheetError> {
+    let (numbers, operators) = input
+        .trim_end_matches('\n')
+        .rsplit_once('\n')
+        .ok_or(WorksheetError::MissingOperators)?;
+    let block = TextBlock::new(numbers);
 
-    Some(
-        vals.iter()
-            .zip(opers.iter())
-            .map(|(v, op)| { Problem { vals: v, op: *op } }.solve())
-            .sum(),
-    )
+    block
+        .column_groups()
+        .iter()
+        .map(|group| {
+            let op = match group.slice(operators).trim() {
+                "+" => Operation::Add,
+                "*" => Operation::Multiply,
+                op => return Err(WorksheetError::UnknownOperator(op.to_string())),
+            };
+            let words: Vec<String> = match reading {
+                Reading::Rows => group.rows().map(str::to_string).collect(),
+                Reading::Columns => group.columns().collect(),
+            };
+            let vals = words
+                .iter()
+                .map(|w| w.trim())
+                .filter(|w| !w.is_empty())
+                .map(|w| {
+                    w.parse()
+                        .map_err(|_| WorksheetError::InvalidNumber(w.to_string()))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Problem { vals, op })
+        })
+        .collect()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let max_j = input.lines().map(|l| l.len()).max().unwrap();
-    let mut vals: Vec<Vec<u64>> = vec![];
-    let mut opers: Vec<Operation> = vec![];
-    let lines: Vec<&[u8]> = input.lines().map(|l| l.as_bytes()).collect();
+fn grand_total(input: &str, reading: Reading) -> Result<u64, WorksheetError> {
+    Ok(read_worksheet(input, reading)?
+        .iter()
+        .map(Problem::solve)
+        .sum())
+}
 
-    let mut new_problem = true;
-    for i in 0..max_j {
-        let i = max_j - i - 1;
-        let mut current_val: Option<u64> = None;
-        if new_problem {
-            vals.push(vec![]);
-        }
-        // by default, let's consider we're on an empty column
-        new_problem = true;
-        for line in &lines {
-            if line.len() > i {
-                match line[i] {
-                    c if c.is_ascii_digit() => {
-                        new_problem = false;
-                        current_val = Some(current_val.unwrap_or(0) * 10 + (c - b'0') as u64);
-                    }
-                    b'*' => {
-                        new_problem = false;
-                        opers.push(Operation::Multiply);
-                    }
-                    b'+' => {
-                        new_problem = false;
-                        opers.push(Operation::Add);
-                    }
-                    b' ' => {}
-                    c => unreachable!("Unexpected character: {}", c),
-                }
-            }
-        }
-        if let Some(val) = current_val {
-            vals.last_mut()?.push(val);
-        }
-    }
-    Some(
-        vals.iter()
-            .zip(opers.iter())
-            .map(|(v, op)| { Problem { vals: v, op: *op } }.solve())
-            .sum(),
-    )
+pub fn part_one(input: &str) -> Result<u64, WorksheetError> {
+    grand_total(input, Reading::Rows)
+}
+
+pub fn part_two(input: &str) -> Result<u64, WorksheetError> {
+    grand_total(input, Reading::Columns)
 }
 
 #[cfg(test)]
@@ -103,12 +104,18 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4277556));
+        assert_eq!(result, Ok(4277556));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3263827));
+        assert_eq!(result, Ok(3263827));
+    }
+
+    #[test]
+    fn test_unknown_operator() {
+        let result = part_two("12 3\n 4 5\n-  *");
+        assert_eq!(result, Err(WorksheetError::UnknownOperator("-".into())));
     }
 }
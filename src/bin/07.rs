@@ -1,95 +1,121 @@
-use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 advent_of_code::solution!(7);
 
-#[derive(Debug, Clone, Eq, PartialEq)]
-struct Point {
-    x: u64,
-    y: u64,
-}
+const WORD_BITS: usize = u64::BITS as usize;
 
 struct Teleporter {
-    start: u64,
-    splitters: Vec<Point>,
-    depth: u64,
+    start: usize,
+    width: usize,
+    /// One bitset of splitter columns per row.
+    splitters: Vec<Vec<u64>>,
 }
 impl FromStr for Teleporter {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut depth = 0;
-        let mut start = u64::MAX;
+        let width = s.lines().map(str::len).max().unwrap_or(0);
+        let mut start = usize::MAX;
         let mut splitters = Vec::new();
         for (j, l) in s.lines().enumerate() {
-            depth += 1;
+            let mut row = vec![0u64; width.div_ceil(WORD_BITS)];
             for (i, c) in l.chars().enumerate() {
                 match c {
                     'S' => {
                         assert_eq!(j, 0);
-                        start = i as u64
+                        start = i
                     }
-                    '^' => splitters.push(Point {
-                        x: i as u64,
-                        y: j as u64,
-                    }),
+                    '^' => row[i / WORD_BITS] |= 1 << (i % WORD_BITS),
                     '.' => {}
                     _ => unreachable!(),
                 }
             }
+            splitters.push(row);
         }
         Ok(Self {
             start,
+            width,
             splitters,
-            depth,
         })
     }
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TimelineOverflow {
+    row: usize,
+}
+impl Display for TimelineOverflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timeline count overflowed on row {}", self.row)
+    }
+}
+
 impl Teleporter {
+    fn is_splitter(&self, row: usize, x: usize) -> bool {
+        self.splitters[row][x / WORD_BITS] >> (x % WORD_BITS) & 1 == 1
+    }
+
+    /// Columns reached when a beam at `x` enters `row`: one or two of them,
+    /// beams split out of the manifold are lost.
+    fn next_columns(&self, row: usize, x: usize) -> impl Iterator<Item = usize> + use<> {
+        let split = self.is_splitter(row, x);
+        let width = self.width;
+        [
+            if split { x.checked_sub(1) } else { Some(x) },
+            if split {
+                Some(x + 1).filter(|x| *x < width)
+            } else {
+                None
+            },
+        ]
+        .into_iter()
+        .flatten()
+    }
+
     fn total_beam_split(&self) -> u64 {
         let mut count = 0;
-        let mut beams: HashSet<u64> = HashSet::new();
-        beams.insert(self.start);
-
-        for j in 0..self.depth {
-            beams = beams
-                .iter()
-                .flat_map(|x| {
-                    if self.splitters.contains(&Point { x: *x, y: j + 1 }) {
-                        count += 1;
-                        [*x - 1, *x + 1].into_iter()
-                    } else {
-                        [*x, *x].into_iter()
-                    }
-                })
-                .collect();
+        let mut beams = vec![false; self.width];
+        beams[self.start] = true;
+
+        for row in 1..self.splitters.len() {
+            let mut next = vec![false; self.width];
+            for x in (0..self.width).filter(|x| beams[*x]) {
+                if self.is_splitter(row, x) {
+                    count += 1;
+                }
+                for nx in self.next_columns(row, x) {
+                    next[nx] = true;
+                }
+            }
+            beams = next;
         }
 
         count
     }
 
-    fn total_timeline(&self) -> u64 {
-        let mut timelines: HashMap<u64, u64> = HashMap::new();
-        timelines.insert(self.start, 1);
-
-        for j in 0..self.depth {
-            timelines = timelines
-                .iter()
-                .flat_map(|(x, count)| {
-                    if self.splitters.contains(&Point { x: *x, y: j + 1 }) {
-                        [Some((*x - 1, *count)), Some((*x + 1, *count))]
-                            .into_iter()
-                            .flatten()
-                    } else {
-                        [Some((*x, *count)), None].into_iter().flatten()
-                    }
-                })
-                .fold(HashMap::new(), |mut acc, (x, count)| {
-                    acc.entry(x).and_modify(|c| *c += count).or_insert(count);
-                    acc
-                });
+    fn total_timeline(&self) -> Result<u128, TimelineOverflow> {
+        let mut timelines = vec![0u128; self.width];
+        timelines[self.start] = 1;
+
+        for row in 1..self.splitters.len() {
+            let mut next = vec![0u128; self.width];
+            for x in (0..self.width).filter(|x| timelines[*x] > 0) {
+                for nx in self.next_columns(row, x) {
+                    next[nx] = next[nx]
+                        .checked_add(timelines[x])
+                        .ok_or(TimelineOverflow { row })?;
+                }
+            }
+            timelines = next;
         }
-        timelines.values().sum()
+
+        timelines
+            .iter()
+            .try_fold(0u128, |acc, c| acc.checked_add(*c))
+            .ok_or(TimelineOverflow {
+                row: self.splitters.len(),
+            })
     }
 }
 pub fn part_one(input: &str) -> Option<u64> {
@@ -98,10 +124,10 @@ pub fn part_one(input: &str) -> Option<u64> {
     Some(teleporter.total_beam_split())
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Result<u128, TimelineOverflow> {
     let teleporter: Teleporter = input.parse().unwrap();
 
-    Some(teleporter.total_timeline())
+    teleporter.total_timeline()
 }
 
 #[cfg(test)]
@@ -117,6 +143,6 @@ mod tests {
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(40));
+        assert_eq!(result, Ok(40));
     }
 }
@@ -1,6 +1,4 @@
 use itertools::Itertools;
-use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 advent_of_code::solution!(8);
@@ -34,165 +32,200 @@ impl Point {
     }
 }
 
-fn connect_boxes(input: &str, count: usize) -> usize {
-    let points: Vec<Point> = input.lines().map(|l| l.parse().unwrap()).collect();
-
-    let known_distances: HashMap<(Point, Point), u64> = points
-        .iter()
-        .flat_map(|p1| points.iter().map(move |p2| (p1, p2)))
-        .filter_map(|(p1, p2)| {
-            if p1 != p2 {
-                Some(((min(*p1, *p2), max(*p1, *p2)), p1.square_distance(p2)))
-            } else {
-                None
-            }
+/// A pair of junction boxes, by index, with their square distance.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Edge {
+    distance: u64,
+    a: usize,
+    b: usize,
+}
+
+/// Every pair of junction boxes, once, in no particular order.
+fn all_edges(points: &[Point]) -> Vec<Edge> {
+    (0..points.len())
+        .flat_map(|a| {
+            (a + 1..points.len()).map(move |b| Edge {
+                distance: points[a].square_distance(&points[b]),
+                a,
+                b,
+            })
         })
-        .collect();
-
-    let mut connected: HashSet<Point> = HashSet::new();
-    let mut connection_set: Vec<HashSet<Point>> = vec![];
-    for ((p1, p2), _) in known_distances
-        .iter()
-        .sorted_by(|(_, d1), (_, d2)| d1.cmp(d2))
-        .take(count)
-    {
-        if connected.contains(p1) {
-            if connected.contains(p2) {
-                let merged_set: HashSet<Point> = connection_set
-                    .iter()
-                    .find(|set| set.contains(p1))
-                    .unwrap()
-                    .union(connection_set.iter().find(|set| set.contains(p2)).unwrap())
-                    .copied()
-                    .collect();
-
-                let mut new_set: Vec<_> = connection_set
-                    .into_iter()
-                    .filter(|set| !(set.contains(p1) || set.contains(p2)))
-                    .collect();
-                new_set.push(merged_set);
-                connection_set = new_set;
-            } else {
-                connection_set = connection_set
-                    .into_iter()
-                    .map(|set| {
-                        if set.contains(p1) {
-                            let mut new_set = set;
-                            new_set.insert(*p2);
-                            new_set
-                        } else {
-                            set
-                        }
-                    })
-                    .collect();
-            }
-        } else if connected.contains(p2) {
-            connection_set = connection_set
-                .into_iter()
-                .map(|set| {
-                    if set.contains(p2) {
-                        let mut new_set = set;
-                        new_set.insert(*p1);
-                        new_set
-                    } else {
-                        set
-                    }
-                })
-                .collect();
-        } else {
-            connection_set.push(HashSet::from_iter([*p1, *p2].into_iter()));
+        .collect()
+}
+
+/// Every pair of junction boxes, by increasing distance. Edges are sorted lazily,
+/// a batch at a time: the shortest ones are partitioned out of the rest, and
+/// only these are sorted. Batches double in size, so reading `k` edges sorts
+/// about `2k` of them.
+struct SortedEdges {
+    edges: Vec<Edge>,
+    /// `edges[..sorted]` is sorted, and shorter than any edge after it.
+    sorted: usize,
+    next: usize,
+    /// Size of the first batch: one edge per box.
+    first_batch: usize,
+}
+impl SortedEdges {
+    fn new(points: &[Point]) -> Self {
+        Self {
+            edges: all_edges(points),
+            sorted: 0,
+            next: 0,
+            first_batch: points.len(),
         }
+    }
 
-        connected.insert(*p1);
-        connected.insert(*p2);
+    fn sort_batch(&mut self) {
+        let batch = self.sorted.max(self.first_batch);
+        let end = (self.sorted + batch).min(self.edges.len());
+        let rest = &mut self.edges[self.sorted..];
+        if end - self.sorted < rest.len() {
+            rest.select_nth_unstable_by_key(end - self.sorted, |e| e.distance);
+        }
+        self.edges[self.sorted..end].sort_unstable_by_key(|e| e.distance);
+        self.sorted = end;
     }
+}
+impl Iterator for SortedEdges {
+    type Item = Edge;
 
-    connection_set
-        .iter()
-        .map(|set| set.len())
-        .sorted_by(|s1, s2| s1.cmp(s2))
-        .rev()
-        .take(3)
-        .product()
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.edges.len() {
+            return None;
+        }
+        if self.next == self.sorted {
+            self.sort_batch();
+        }
+        self.next += 1;
+        Some(self.edges[self.next - 1])
+    }
 }
 
-fn connect_last(input: &str) -> u64 {
-    let points: Vec<Point> = input.lines().map(|l| l.parse().unwrap()).collect();
-
-    let known_distances: HashMap<(Point, Point), u64> = points
-        .iter()
-        .flat_map(|p1| points.iter().map(move |p2| (p1, p2)))
-        .filter_map(|(p1, p2)| {
-            if p1 != p2 {
-                Some(((min(*p1, *p2), max(*p1, *p2)), p1.square_distance(p2)))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    let mut connected: HashSet<Point> = HashSet::new();
-    let mut connection_set: Vec<HashSet<Point>> = vec![];
-
-    for ((p1, p2), _) in known_distances
-        .iter()
-        .sorted_by(|(_, d1), (_, d2)| d1.cmp(d2))
-    {
-        if connected.contains(p1) {
-            if connected.contains(p2) {
-                let merged_set: HashSet<Point> = connection_set
-                    .iter()
-                    .find(|set| set.contains(p1))
-                    .unwrap()
-                    .union(connection_set.iter().find(|set| set.contains(p2)).unwrap())
-                    .copied()
-                    .collect();
-
-                let mut new_set: Vec<_> = connection_set
-                    .into_iter()
-                    .filter(|set| !(set.contains(p1) || set.contains(p2)))
-                    .collect();
-                new_set.push(merged_set);
-                connection_set = new_set;
-            } else {
-                connection_set = connection_set
-                    .into_iter()
-                    .map(|set| {
-                        if set.contains(p1) {
-                            let mut new_set = set;
-                            new_set.insert(*p2);
-                            new_set
-                        } else {
-                            set
-                        }
-                    })
-                    .collect();
-            }
-        } else if connected.contains(p2) {
-            connection_set = connection_set
-                .into_iter()
-                .map(|set| {
-                    if set.contains(p2) {
-                        let mut new_set = set;
-                        new_set.insert(*p1);
-                        new_set
-                    } else {
-                        set
-                    }
-                })
-                .collect();
-        } else {
-            connection_set.push(HashSet::from_iter([*p1, *p2].into_iter()));
+/// Union-find over box indexes, tracking the size of every circuit.
+struct Circuits {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+impl Circuits {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Connects the circuits of `a` and `b`, returns false if they already were.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    fn sizes(&self) -> Vec<usize> {
+        (0..self.parent.len())
+            .filter(|i| self.parent[*i] == *i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+}
+
+fn read_points(input: &str) -> Vec<Point> {
+    input.lines().map(|l| l.parse().unwrap()).collect()
+}
+
+/// One step of the merge history: the edge that joined two circuits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Merge {
+    /// Position of the edge among the edges consumed so far (merging or not).
+    rank: usize,
+    edge: Edge,
+    /// Sizes of the two circuits joined by `edge`.
+    merged_sizes: (usize, usize),
+    /// Number of circuits left after this merge.
+    circuits_left: usize,
+}
+
+/// Single-linkage clustering: edges are consumed by increasing distance, and
+/// every edge joining two different circuits yields a [`Merge`]. Collecting
+/// every merge gives the dendrogram.
+struct Clustering<I> {
+    edges: std::iter::Enumerate<I>,
+    circuits: Circuits,
+}
+impl<I: Iterator<Item = Edge>> Clustering<I> {
+    /// `edges` must come by increasing distance, e.g. from [`SortedEdges`].
+    fn new(len: usize, edges: I) -> Self {
+        Self {
+            edges: edges.enumerate(),
+            circuits: Circuits::new(len),
         }
+    }
+
+    /// Sizes of the circuits, given the edges consumed so far.
+    fn sizes(&self) -> Vec<usize> {
+        self.circuits.sizes()
+    }
+}
+impl<I: Iterator<Item = Edge>> Iterator for Clustering<I> {
+    type Item = Merge;
 
-        connected.insert(*p1);
-        connected.insert(*p2);
-        if connected.len() == points.len() && connection_set.len() == 1 {
-            return (p1.x * p2.x) as u64;
+    fn next(&mut self) -> Option<Self::Item> {
+        for (rank, edge) in self.edges.by_ref() {
+            let (a, b) = (self.circuits.find(edge.a), self.circuits.find(edge.b));
+            let merged_sizes = (self.circuits.size[a], self.circuits.size[b]);
+            if self.circuits.union(a, b) {
+                return Some(Merge {
+                    rank,
+                    edge,
+                    merged_sizes,
+                    circuits_left: self.circuits.count,
+                });
+            }
         }
+        None
     }
+}
+
+/// Product of the 3 largest circuits after connecting the `count` closest pairs.
+fn connect_boxes(input: &str, count: usize) -> usize {
+    let points = read_points(input);
+    let mut clustering = Clustering::new(points.len(), SortedEdges::new(&points).take(count));
+    clustering.by_ref().for_each(drop);
+
+    clustering
+        .sizes()
+        .into_iter()
+        .sorted_by(|s1, s2| s1.cmp(s2))
+        .rev()
+        .take(3)
+        .product()
+}
 
-    unreachable!()
+/// The merge making a single circuit is the last edge of the minimum spanning tree.
+fn connect_last(input: &str) -> u64 {
+    let points = read_points(input);
+    let last = Clustering::new(points.len(), SortedEdges::new(&points))
+        .find(|merge| merge.circuits_left == 1)
+        .unwrap();
+    (points[last.edge.a].x * points[last.edge.b].x) as u64
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
@@ -213,6 +246,36 @@ mod tests {
         assert_eq!(result, 40);
     }
 
+    #[test]
+    fn test_merge_history() {
+        let points = read_points(&advent_of_code::template::read_file("examples", DAY));
+        let history: Vec<Merge> =
+            Clustering::new(points.len(), SortedEdges::new(&points)).collect();
+
+        assert_eq!(history.len(), points.len() - 1);
+        assert_eq!(history[0].merged_sizes, (1, 1));
+        assert!(history.windows(2).all(|w| w[0].rank < w[1].rank));
+        assert_eq!(history.last().unwrap().circuits_left, 1);
+        let last = history.last().unwrap().merged_sizes;
+        assert_eq!(last.0 + last.1, points.len());
+    }
+
+    #[test]
+    fn test_sorted_edges() {
+        let points = read_points(&advent_of_code::template::read_file("examples", DAY));
+        let edges: Vec<Edge> = SortedEdges::new(&points).collect();
+        assert_eq!(edges.len(), points.len() * (points.len() - 1) / 2);
+        assert!(edges.is_sorted_by_key(|e| e.distance));
+
+        let mut all = all_edges(&points);
+        all.sort_unstable_by_key(|e| e.distance);
+        assert_eq!(
+            edges.iter().map(|e| e.distance).collect::<Vec<_>>(),
+            all.iter().map(|e| e.distance).collect::<Vec<_>>()
+        );
+        assert_eq!(SortedEdges::new(&[]).next(), None);
+    }
+
     #[test]
     fn test_part_two() {
         let result = connect_last(&advent_of_code::template::read_file("examples", DAY));
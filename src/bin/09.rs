@@ -1,17 +1,15 @@
+use advent_of_code::geometry::{Point, PolygonError, RectilinearPolygon};
+use advent_of_code::template::flags::{Flags, write_debug_output};
 use itertools::Itertools;
 use rayon::prelude::*;
-use std::cmp::{max, min};
-use std::collections::HashSet;
+use std::cmp::{Reverse, max};
+use std::error::Error;
+use std::fmt::Write;
 
-advent_of_code::solution!(9);
+advent_of_code::solution!(9, debug: write_svg);
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
-struct Point {
-    x: u64,
-    y: u64,
-}
-pub fn part_one(input: &str) -> Option<u64> {
-    let red_tiles: Vec<_> = input
+fn read_red_tiles(input: &str) -> Vec<Point> {
+    input
         .trim()
         .lines()
         .map(|l| {
@@ -23,143 +21,144 @@ pub fn part_one(input: &str) -> Option<u64> {
                 .unwrap();
             Point { x, y }
         })
-        .collect();
+        .collect()
+}
+
+fn area(p: &Point, p2: &Point) -> u64 {
+    (p2.x.abs_diff(p.x) + 1) * (p2.y.abs_diff(p.y) + 1)
+}
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let red_tiles = read_red_tiles(input);
     red_tiles
         .iter()
-        .filter_map(|p| {
-            red_tiles
-                .iter()
-                .map(|p2| (p2.x.abs_diff(p.x) + 1) * (p2.y.abs_diff(p.y) + 1))
-                .reduce(max)
-        })
+        .filter_map(|p| red_tiles.iter().map(|p2| area(p, p2)).reduce(max))
         .reduce(max)
 }
 
-fn is_rect_redgreen_only(rect: (Point, Point), vert_edges: &[(u64, (u64, u64))]) -> bool {
-    let upperleft = Point {
-        x: min(2 * rect.0.x, 2 * rect.1.x),
-        y: min(2 * rect.0.y, 2 * rect.1.y),
-    };
-    let lowerright = Point {
-        x: max(2 * rect.0.x, 2 * rect.1.x),
-        y: max(2 * rect.0.y, 2 * rect.1.y),
-    };
-
-    let considered_edges: HashSet<(u64, (u64, u64))> = vert_edges
-        .iter()
-        .map(|(col, (top, bottom))| (2 * *col, (2 * *top, 2 * *bottom)))
-        .filter(|(col, (top, bottom))| {
-            *col <= lowerright.x
-                && ((upperleft.y..=lowerright.y).contains(top)
-                    || (upperleft.y..=lowerright.y).contains(bottom)
-                    || (*top..=*bottom).contains(&upperleft.y))
+/// Largest rectangle with red corners made of red and green tiles only.
+fn largest_redgreen_rect(polygon: &RectilinearPolygon) -> Option<(Point, Point)> {
+    let red_tiles = polygon.vertices();
+    red_tiles
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, p)| {
+            red_tiles[i + 1..]
+                .iter()
+                .filter(|p2| polygon.contains_rect(*p, **p2))
+                .max_by_key(|p2| area(p, p2))
+                .map(|p2| (*p, *p2))
         })
-        .collect();
+        .max_by_key(|(p, p2)| area(p, p2))
+}
 
-    let mut intersting_y: HashSet<_> = considered_edges
+/// Rectangles larger than `best` that were rejected, largest first.
+fn rejected_rects(
+    polygon: &RectilinearPolygon,
+    best: Option<(Point, Point)>,
+    count: usize,
+) -> Vec<(Point, Point)> {
+    let best_area = best.map_or(0, |(p, p2)| area(&p, &p2));
+    polygon
+        .vertices()
         .iter()
-        .flat_map(|(_, (top, bottom))| [*top, *bottom].into_iter())
-        .filter(|l| *l > upperleft.y && *l < lowerright.y)
-        .collect();
-    intersting_y.insert(upperleft.y);
-    intersting_y.insert(lowerright.y);
+        .tuple_combinations()
+        .filter(|(p, p2)| area(p, p2) > best_area && !polygon.contains_rect(**p, **p2))
+        .map(|(p, p2)| (*p, *p2))
+        .sorted_by_key(|(p, p2)| Reverse(area(p, p2)))
+        .take(count)
+        .collect()
+}
 
-    let intersting_y = intersting_y
+/// Renders the red tiles polygon, the rejected candidates (dashed) and the
+/// best rectangle, scaled so that the longest side is 800px.
+fn render_svg(
+    polygon: &RectilinearPolygon,
+    best: Option<(Point, Point)>,
+    rejected: &[(Point, Point)],
+) -> String {
+    const SIZE: f64 = 800.0;
+    let (min, max) = polygon.bounds();
+    let (w, h) = (max.x - min.x + 1, max.y - min.y + 1);
+    let scale = SIZE / w.max(h) as f64;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {w} {h}" width="{:.0}" height="{:.0}">"#,
+        min.x,
+        min.y,
+        w as f64 * scale,
+        h as f64 * scale
+    );
+    // tiles are unit squares, the polygon goes through the center of the red ones.
+    let points = polygon
+        .vertices()
         .iter()
-        .flat_map(|l| [*l, *l + 1].into_iter())
-        .filter(|l| *l <= lowerright.y)
-        .sorted()
-        .collect_vec();
-
-    for y in intersting_y {
-        let considered_edges: Vec<(u64, (u64, u64))> = considered_edges
-            .iter()
-            .filter(|(_, (top, bottom))| (*top..=*bottom).contains(&y))
-            .sorted_by(|(col1, _), (col2, _)| col1.cmp(col2))
-            .copied()
-            .collect();
-
-        assert!(upperleft.x > 0);
-
-        if considered_edges
-            .first()
-            .filter(|(col, _)| *col <= upperleft.x)
-            .is_none()
-        {
-            return false;
-        }
-        let (mut previous_col, (mut previous_top, mut previous_bottom)) =
-            considered_edges.first().copied().unwrap();
-        let mut in_green_surface = true;
-
-        for (col, (top, bottom)) in considered_edges.iter().skip(1) {
-            if !in_green_surface && *col > previous_col + 1 {
-                return false;
-            }
-
-            // if we're not on a horizontal edge, extended in the same direction,
-            // then we're crossing a boundary, thus switching mode
-            if previous_top != *bottom && previous_bottom != *top {
-                in_green_surface = !in_green_surface;
-            }
-
-            previous_col = *col;
-            previous_top = *top;
-            previous_bottom = *bottom;
-        }
-        if !in_green_surface && previous_col != lowerright.x {
-            return false;
-        }
+        .map(|p| format!("{}.5,{}.5", p.x, p.y))
+        .join(" ");
+    let _ = writeln!(
+        svg,
+        r#"<polygon points="{points}" fill="green" fill-opacity="0.3" stroke="red" stroke-width="1" vector-effect="non-scaling-stroke"/>"#
+    );
+    let rect = |(p, p2): &(Point, Point), style: &str| {
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" {style} vector-effect="non-scaling-stroke"/>"#,
+            p.x.min(p2.x),
+            p.y.min(p2.y),
+            p.x.abs_diff(p2.x) + 1,
+            p.y.abs_diff(p2.y) + 1
+        )
+    };
+    for r in rejected {
+        let _ = writeln!(
+            svg,
+            "{}",
+            rect(r, r#"fill="none" stroke="gray" stroke-dasharray="4 2""#)
+        );
+    }
+    if let Some(best) = best {
+        let _ = writeln!(
+            svg,
+            "{}",
+            rect(
+                &best,
+                r#"fill="blue" fill-opacity="0.3" stroke="blue" stroke-width="2""#
+            )
+        );
+    }
+    for p in polygon.vertices() {
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{}.5" cy="{}.5" r="{}" fill="red"/>"#,
+            p.x,
+            p.y,
+            2.0 / scale
+        );
     }
+    svg.push_str("</svg>\n");
+    svg
+}
 
-    true
+/// Debug output of part two, e.g. `cargo run --bin 09 -- --svg day09.svg --svg-rejected 10`.
+fn write_svg(input: &str, flags: &Flags) -> Result<(), Box<dyn Error>> {
+    let Some(path) = flags.value("--svg") else {
+        return Ok(());
+    };
+    let rejected_count = flags.parse("--svg-rejected")?.unwrap_or(0);
+    let polygon = RectilinearPolygon::new(read_red_tiles(input))?;
+    let best = largest_redgreen_rect(&polygon);
+    let rejected = rejected_rects(&polygon, best, rejected_count);
+    write_debug_output(path, &render_svg(&polygon, best, &rejected));
+    Ok(())
 }
-pub fn part_two(input: &str) -> Option<u64> {
-    let red_tiles: Vec<_> = input
-        .trim()
-        .lines()
-        .map(|l| {
-            let (x, y) = l
-                .trim()
-                .split(',')
-                .map(|w| w.parse().unwrap())
-                .collect_tuple()
-                .unwrap();
-            Point { x, y }
-        })
-        .collect();
-    let len = red_tiles.len();
-    assert_eq!(len, red_tiles.iter().unique().count());
-    let vert_edges: Vec<(u64, (u64, u64))> = red_tiles
-        .iter()
-        .enumerate()
-        .filter_map(|(i, p)| {
-            let next_p = red_tiles[(i + 1) % len];
-            if next_p.x == p.x {
-                Some((p.x, (min(p.y, next_p.y), max(p.y, next_p.y))))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    Some(
-        red_tiles
-            .par_iter()
-            .filter_map(|p| {
-                red_tiles
-                    .iter()
-                    .filter_map(|p2| {
-                        if p2 != p && is_rect_redgreen_only((*p, *p2), &vert_edges) {
-                            Some((p2.x.abs_diff(p.x) + 1) * (p2.y.abs_diff(p.y) + 1))
-                        } else {
-                            None
-                        }
-                    })
-                    .reduce(max)
-            })
-            .reduce(|| 0u64, max),
-    )
+
+pub fn part_two(input: &str) -> Result<u64, PolygonError> {
+    let red_tiles = read_red_tiles(input);
+    let polygon = RectilinearPolygon::new(red_tiles)?;
+
+    let best = largest_redgreen_rect(&polygon);
+    Ok(best.map_or(0, |(p, p2)| area(&p, &p2)))
 }
 
 #[cfg(test)]
@@ -172,9 +171,23 @@ mod tests {
         assert_eq!(result, Some(50));
     }
 
+    #[test]
+    fn test_render_svg() {
+        let red_tiles = read_red_tiles(&advent_of_code::template::read_file("examples", DAY));
+        let polygon = RectilinearPolygon::new(red_tiles).unwrap();
+        let best = largest_redgreen_rect(&polygon);
+        let rejected = rejected_rects(&polygon, best, 3);
+        assert_eq!(rejected.len(), 3);
+
+        let svg = render_svg(&polygon, best, &rejected);
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches("<rect ").count(), 4);
+        assert_eq!(svg.matches("<circle ").count(), polygon.vertices().len());
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(24));
+        assert_eq!(result, Ok(24));
     }
 }
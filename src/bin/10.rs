@@ -1,5 +1,7 @@
+use advent_of_code::gf2::{BitMatrix, BitVector};
 use itertools::Itertools;
 use std::cmp::min;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 #[cfg(feature = "z3")]
@@ -10,6 +12,27 @@ use std::collections::HashMap;
 
 advent_of_code::solution!(10);
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum FactoryError {
+    Z3Disabled,
+    LightsUnreachable(usize),
+    NoSolution(usize),
+}
+impl Display for FactoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FactoryError::Z3Disabled => write!(
+                f,
+                "disabled because z3 build is too slow. Use `cargo run --features=z3 --bin 10` to get the actual solution"
+            ),
+            FactoryError::LightsUnreachable(line) => {
+                write!(f, "the buttons of line {line} cannot reach its lights")
+            }
+            FactoryError::NoSolution(line) => write!(f, "no button presses match line {line}"),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct FactoryLine {
     lights: Vec<bool>,
@@ -68,36 +91,25 @@ impl FromStr for FactoryLine {
     }
 }
 impl FactoryLine {
-    fn min_number_of_press(&self) -> usize {
-        let max_len = self.buttons.len();
-        // at most one press
-        (0..=max_len)
-            .find({
-                |k| {
-                    let k = *k;
-                    (0..max_len).combinations(k).any(|buttons| {
-                        let mut lights_status = vec![false; self.lights.len()];
-                        for b_idx in buttons {
-                            for light_idx in &self.buttons[b_idx] {
-                                lights_status[*light_idx] = !lights_status[*light_idx];
-                            }
-                        }
-                        lights_status
-                            .iter()
-                            .zip(self.lights.iter())
-                            .all(|(status, target)| *status == *target)
-                    })
-                }
-            })
-            .unwrap()
+    /// Lights are a linear system over GF(2): one equation per light, one
+    /// unknown per button, since pressing a button twice is a no-op.
+    fn min_number_of_press(&self) -> Option<usize> {
+        let mut toggles = BitMatrix::zeros(self.lights.len(), self.buttons.len());
+        for (b_idx, button) in self.buttons.iter().enumerate() {
+            for light_idx in button {
+                toggles.set(*light_idx, b_idx, true);
+            }
+        }
+        let target: BitVector = self.lights.iter().copied().collect();
+
+        let solutions = toggles.solve(&target)?;
+        Some(solutions.min_weight().count_ones() as usize)
     }
 
     #[cfg(not(feature = "z3"))]
     fn min_number_of_press_joltages(&self) -> Option<usize> {
         let _ = self.joltages;
-        todo!(
-            "disabled because z3 build is too slow. Use cargo run --features=z3 --bin 10  to get the actual solution"
-        )
+        None
     }
     #[cfg(feature = "z3")]
     fn min_number_of_press_joltages(&self) -> Option<usize> {
@@ -159,26 +171,40 @@ impl FromStr for Factory {
     }
 }
 impl Factory {
-    fn min_number_of_press(&self) -> usize {
-        self.lines.iter().map(|l| l.min_number_of_press()).sum()
+    fn min_number_of_press(&self) -> Result<usize, FactoryError> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, l)| {
+                l.min_number_of_press()
+                    .ok_or(FactoryError::LightsUnreachable(i + 1))
+            })
+            .sum()
     }
 
-    fn min_number_of_press_joltages(&self) -> usize {
+    fn min_number_of_press_joltages(&self) -> Result<usize, FactoryError> {
+        if cfg!(not(feature = "z3")) {
+            return Err(FactoryError::Z3Disabled);
+        }
         self.lines
             .iter()
-            .map(|l| l.min_number_of_press_joltages().unwrap())
+            .enumerate()
+            .map(|(i, l)| {
+                l.min_number_of_press_joltages()
+                    .ok_or(FactoryError::NoSolution(i + 1))
+            })
             .sum()
     }
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
+pub fn part_one(input: &str) -> Result<u64, FactoryError> {
     let factory = Factory::from_str(input).unwrap();
-    Some(factory.min_number_of_press() as u64)
+    Ok(factory.min_number_of_press()? as u64)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Result<u64, FactoryError> {
     let factory = Factory::from_str(input).unwrap();
-    Some(factory.min_number_of_press_joltages() as u64)
+    Ok(factory.min_number_of_press_joltages()? as u64)
 }
 
 #[cfg(test)]
@@ -190,25 +216,39 @@ mod tests {
         let line: FactoryLine = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}"
             .parse()
             .unwrap();
-        assert_eq!(line.min_number_of_press(), 2);
+        assert_eq!(line.min_number_of_press(), Some(2));
 
         let line: FactoryLine = "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}"
             .parse()
             .unwrap();
-        assert_eq!(line.min_number_of_press(), 3);
+        assert_eq!(line.min_number_of_press(), Some(3));
 
         let line: FactoryLine = "[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}"
             .parse()
             .unwrap();
-        assert_eq!(line.min_number_of_press(), 2);
+        assert_eq!(line.min_number_of_press(), Some(2));
 
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(7));
+        assert_eq!(result, Ok(7));
+    }
+
+    #[test]
+    fn test_part_one_unreachable_lights() {
+        let result = part_one("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n[#.] (1) {1,1}");
+        assert_eq!(result, Err(FactoryError::LightsUnreachable(2)));
     }
 
     #[test]
+    #[cfg_attr(not(feature = "z3"), ignore = "requires the z3 feature")]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(33));
+        assert_eq!(result, Ok(33));
+    }
+
+    #[test]
+    #[cfg(not(feature = "z3"))]
+    fn test_part_two_without_z3() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Err(FactoryError::Z3Disabled));
     }
 }
@@ -1,105 +1,77 @@
-use std::collections::{HashMap, HashSet};
+use advent_of_code::graph::{CycleError, Dag, Graph};
+use advent_of_code::template::flags::{Flags, write_debug_output};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Write;
 
-advent_of_code::solution!(11);
+advent_of_code::solution!(11, debug: write_dot);
 
-fn count_path(all_path: &HashMap<&str, HashSet<&str>>, entry: &str, out: &str) -> u64 {
-    let mut out_by_entry: HashMap<&str, (usize, Vec<&str>)> = all_path
-        .iter()
-        .map(|(entry, outs)| {
-            (
-                *entry,
-                (
-                    outs.iter().filter(|o| **o == out).count(),
-                    outs.iter()
-                        .filter(|o| **o != out)
-                        .copied()
-                        .collect::<Vec<&str>>(),
-                ),
-            )
-        })
-        .collect();
-
-    if !out_by_entry.contains_key(entry) {
-        println!("no {entry} in {out_by_entry:?}");
-    }
-    while !out_by_entry[entry].1.is_empty() {
-        let new_out_by_entry: HashMap<&str, (usize, Vec<&str>)> = all_path
-            .keys()
-            .map(|entry| {
-                let (count, outs) = &out_by_entry[entry];
-                let new_count: usize = count
-                    + outs
-                        .iter()
-                        .map(|e| {
-                            if let Some((inner_count, inner_outs)) = out_by_entry.get(e)
-                                && inner_outs.is_empty()
-                            {
-                                *inner_count
-                            } else {
-                                0
-                            }
-                        })
-                        .sum::<usize>();
-                let new_outs = outs
-                    .iter()
-                    .filter(|e| {
-                        !out_by_entry
-                            .get(*e)
-                            .map(|(_, unresolved)| unresolved.is_empty())
-                            .unwrap_or(true)
-                    })
-                    .cloned()
-                    .collect();
-
-                (*entry, (new_count, new_outs))
-            })
-            .collect();
-        out_by_entry = new_out_by_entry;
+fn read_reactor(input: &str) -> Result<Dag<&str>, CycleError<&str>> {
+    let mut graph = Graph::new();
+    for l in input.lines() {
+        let (entry, outs) = l.split_once(':').unwrap();
+        graph.add_node(entry);
+        for out in outs.split_ascii_whitespace() {
+            graph.add_edge(entry, out);
+        }
     }
-
-    out_by_entry[entry].0 as u64
+    graph.into_dag()
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let path: HashMap<&str, HashSet<&str>> = input
-        .lines()
-        .map(|l| {
-            let entry: &str = &l[..l.find(':').unwrap()];
-            let outs: HashSet<&str> = l[l.find(':').unwrap() + 1..]
-                .split_ascii_whitespace()
-                .collect();
+/// Nodes the puzzle's paths start from or must go through.
+const WAYPOINTS: [&str; 4] = ["you", "svr", "fft", "dac"];
 
-            (entry, outs)
-        })
-        .collect();
+/// Graphviz DOT export of the reactor, every node labelled with its number of
+/// paths to `out`. If `from` is set, only the nodes reachable from it are kept.
+fn to_dot(reactor: &Dag<&str>, from: Option<&str>) -> String {
+    let graph = reactor.graph();
+    let kept: HashSet<&str> = match from {
+        Some(from) => graph.reachable_from(from).into_iter().collect(),
+        None => graph.nodes().iter().copied().collect(),
+    };
+    let paths_to_out = reactor.count_paths_to("out");
 
-    Some(count_path(&path, "you", "out"))
+    let mut dot = String::from("digraph reactor {\n    rankdir=LR;\n    node [shape=box];\n");
+    for (node, paths) in graph.nodes().iter().zip(&paths_to_out) {
+        if !kept.contains(node) {
+            continue;
+        }
+        let style = if WAYPOINTS.contains(node) {
+            ", style=filled, fillcolor=gold"
+        } else if *node == "out" {
+            ", style=filled, fillcolor=lightgreen"
+        } else {
+            ""
+        };
+        let _ = writeln!(dot, r#"    "{node}" [label="{node}\n{paths}"{style}];"#);
+    }
+    for node in reactor.topological_order().filter(|n| kept.contains(n)) {
+        for out in graph.successors(node) {
+            let _ = writeln!(dot, r#"    "{node}" -> "{out}";"#);
+        }
+    }
+    dot.push_str("}\n");
+    dot
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let path: HashMap<&str, HashSet<&str>> = input
-        .lines()
-        .map(|l| {
-            let entry: &str = &l[..l.find(':').unwrap()];
-            let outs: HashSet<&str> = l[l.find(':').unwrap() + 1..]
-                .split_ascii_whitespace()
-                .collect();
-
-            (entry, outs)
-        })
-        .collect();
+/// Debug output, e.g. `cargo run --bin 11 -- --dot day11.dot --dot-from svr`.
+fn write_dot(input: &str, flags: &Flags) -> Result<(), Box<dyn Error>> {
+    let Some(path) = flags.value("--dot") else {
+        return Ok(());
+    };
+    // the cycle borrows the input, only its description outlives this call.
+    let reactor = read_reactor(input).map_err(|e| e.to_string())?;
+    write_debug_output(path, &to_dot(&reactor, flags.value("--dot-from")));
+    Ok(())
+}
 
-    let dac_to_out_count = count_path(&path, "dac", "out");
-    let fft_to_out_count = count_path(&path, "fft", "out");
-    let dac_to_fft_count = count_path(&path, "dac", "fft");
-    let fft_to_dac_count = count_path(&path, "fft", "dac");
-    let srv_to_fft_count = count_path(&path, "svr", "fft"); // svr, not srv !
-    let srv_to_dac_count = count_path(&path, "svr", "dac");
+pub fn part_one(input: &str) -> Result<u64, CycleError<&str>> {
+    Ok(read_reactor(input)?.count_paths("you", "out"))
+}
 
-    Some(
-        srv_to_fft_count * fft_to_dac_count * dac_to_out_count
-            + srv_to_dac_count * dac_to_fft_count * fft_to_out_count,
-    )
+pub fn part_two(input: &str) -> Result<u64, CycleError<&str>> {
+    let reactor = read_reactor(input)?;
+    Ok(reactor.count_paths_through("svr", "out", &["fft", "dac"]))
 }
 
 #[cfg(test)]
@@ -108,8 +80,23 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(5));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(part_one(&input), Ok(5));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let reactor = read_reactor(&input).unwrap();
+
+        let dot = to_dot(&reactor, None);
+        assert!(dot.starts_with("digraph reactor {"));
+        assert!(dot.contains(r#""you" [label="you\n5", style=filled"#));
+        assert!(dot.contains(r#""out" [label="out\n1""#));
+
+        let from_you = to_dot(&reactor, Some("you"));
+        assert!(from_you.lines().count() < dot.lines().count());
+        assert!(!from_you.contains(r#""aaa" ["#));
     }
 
     #[test]
@@ -129,6 +116,6 @@ fff: ggg hhh
 ggg: out
 hhh: out",
         );
-        assert_eq!(result, Some(2));
+        assert_eq!(result, Ok(2));
     }
 }
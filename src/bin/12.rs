@@ -1,8 +1,13 @@
+use advent_of_code::polyomino::{Packing, Placement, Shape};
+use advent_of_code::template::flags::Flags;
 use itertools::Itertools;
+use rayon::prelude::*;
+use std::error::Error;
 use std::num::TryFromIntError;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
-advent_of_code::solution!(12);
+advent_of_code::solution!(12, debug: report);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 struct Point {
@@ -140,124 +145,80 @@ impl FromStr for Cavern {
     }
 }
 
-#[non_exhaustive]
-#[derive(Debug, PartialEq, Eq)]
-struct TreeSpace {
-    occupied: Vec<bool>, // must be always sorted
-    shape: Point,
+/// The rule that decided whether the presents fit under a tree.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Rule {
+    /// There is room for every present in its own box of the largest present size.
+    BoxGrid,
+    /// The presents cells outnumber the cells under the tree.
+    AreaTooSmall,
+    /// The full packing search.
+    Search,
 }
-impl TreeSpace {
-    fn new(shape: Point) -> Self {
-        Self {
-            occupied: vec![false; shape.x as usize * shape.y as usize],
-            shape,
-        }
-    }
-    fn prev_pos(&self, current_pos: Point) -> Option<Point> {
-        if current_pos.x > 0 {
-            Some(Point {
-                x: current_pos.x - 1,
-                y: current_pos.y,
-            })
-        } else if current_pos.y > 0 {
-            Some(Point {
-                x: self.shape.x - 1,
-                y: current_pos.y - 1,
-            })
-        } else {
-            None
-        }
-    }
-    fn next_pos(&self, current_pos: Point) -> Option<Point> {
-        if current_pos.x + 1 < self.shape.x {
-            Some(Point {
-                x: current_pos.x + 1,
-                y: current_pos.y,
-            })
-        } else if current_pos.y + 1 < self.shape.y {
-            Some(Point {
-                x: 0,
-                y: current_pos.y + 1,
-            })
-        } else {
-            None
+
+impl Rule {
+    /// What the rule says, [`None`] if it takes a search to know.
+    fn fits(self) -> Option<bool> {
+        match self {
+            Rule::BoxGrid => Some(true),
+            Rule::AreaTooSmall => Some(false),
+            Rule::Search => None,
         }
     }
+}
 
-    fn occupied(&self, pos: &Point) -> bool {
-        self.occupied[pos.y as usize * self.shape.x as usize + pos.x as usize]
-    }
-    fn insert(&mut self, pos: &Point) {
-        debug_assert!(!self.occupied(pos));
-        self.occupied[pos.y as usize * self.shape.x as usize + pos.x as usize] = true;
-    }
-    fn remove(&mut self, pos: &Point) {
-        self.occupied[pos.y as usize * self.shape.x as usize + pos.x as usize] = false;
+/// How the presents of a tree were checked.
+#[derive(Debug)]
+struct Decision {
+    rule: Rule,
+    /// The heuristic that applied but was overridden by the search, in exact mode.
+    skipped: Option<Rule>,
+    placements: Option<Vec<Placement>>,
+    elapsed: Duration,
+}
+
+impl Decision {
+    fn fits(&self) -> bool {
+        self.placements.is_some()
     }
 
-    fn place_present(&mut self, present: &Present, pos: &Point) -> Result<(), ()> {
-        if present.occupied.iter().any(|p| {
-            p.x + pos.x >= self.shape.x
-                || p.y + pos.y >= self.shape.y
-                || self.occupied(&Point {
-                    x: p.x + pos.x,
-                    y: p.y + pos.y,
-                })
-        }) {
-            Err(())
-        } else {
-            for p in &present.occupied {
-                self.insert(&Point {
-                    x: p.x + pos.x,
-                    y: p.y + pos.y,
-                });
-            }
-            Ok(())
-        }
+    /// Whether a skipped heuristic would have given another answer than the search.
+    fn disagrees(&self) -> bool {
+        self.skipped
+            .and_then(Rule::fits)
+            .is_some_and(|fits| fits != self.fits())
     }
+}
 
-    fn remove_present(&mut self, present: &Present, pos: &Point) {
-        for p in &present.occupied {
-            self.remove(&Point {
-                x: p.x + pos.x,
-                y: p.y + pos.y,
-            });
+/// Command line switches of the [`report`], e.g. `cargo run --bin 12 -- --explain --exact`.
+/// Part one itself never reads them.
+#[derive(Debug, Copy, Clone)]
+struct Options {
+    /// Always run the full search, even when a heuristic applies.
+    exact: bool,
+    /// Report which rule decided each tree, and how long it took.
+    explain: bool,
+    /// Draw every arrangement found.
+    render: bool,
+}
+
+impl Options {
+    fn from_flags(flags: &Flags) -> Self {
+        Self {
+            exact: flags.has("--exact"),
+            explain: flags.has("--explain"),
+            render: flags.has("--render"),
         }
     }
 }
 
 impl Cavern {
-    fn next_available_shape(
-        current_shape: &Option<(u8, u8)>,
-        missings: &[usize],
-        variant_counts: &[usize],
-    ) -> Option<(u8, u8)> {
-        if let Some((cur_p, cur_v)) = current_shape {
-            if (*cur_v as usize) < (variant_counts[*cur_p as usize] - 1) {
-                Some((*cur_p, *cur_v + 1))
-            } else {
-                missings
-                    .iter()
-                    .enumerate()
-                    .skip(*cur_p as usize + 1)
-                    .find(|(_, m)| **m > 0)
-                    .and_then(|(i, _)| i.try_into().ok().map(|i| (i, 0u8)))
-            }
-        } else {
-            missings
-                .iter()
-                .enumerate()
-                .find(|(_, m)| **m > 0)
-                .and_then(|(i, _)| i.try_into().ok().map(|i| (i, 0u8)))
-        }
-    }
-    fn presents_fit_under_tree(&self, tree: &Tree, all_shapes: &[Vec<Present>]) -> bool {
-        let max_shape_w = all_shapes.iter().flatten().map(|v| v.maxx).max().unwrap() as usize + 1;
-        let max_shape_h = all_shapes.iter().flatten().map(|v| v.maxy).max().unwrap() as usize + 1;
-        if tree.shape.x as usize / max_shape_w * tree.shape.y as usize / max_shape_h
+    fn heuristic(tree: &Tree, all_shapes: &[Vec<Present>]) -> Option<Rule> {
+        let (max_shape_w, max_shape_h) = Self::max_shape_size(all_shapes);
+        if tree.shape.x as usize / max_shape_w * (tree.shape.y as usize / max_shape_h)
             >= tree.presents.iter().copied().sum()
         {
-            return true;
+            return Some(Rule::BoxGrid);
         }
         if (tree.shape.x as usize * tree.shape.y as usize)
             < (tree
@@ -267,100 +228,179 @@ impl Cavern {
                 .map(|(pre_idx, count)| all_shapes[pre_idx][0].occupied.len() * *count)
                 .sum())
         {
-            return false;
+            return Some(Rule::AreaTooSmall);
         }
+        None
+    }
 
-        eprintln!("actual search");
-        let variant_counts: Vec<usize> = all_shapes.iter().map(|v| v.len()).collect();
-
-        let mut missing_presents = tree.presents.clone();
+    fn max_shape_size(all_shapes: &[Vec<Present>]) -> (usize, usize) {
+        let max_shape_w = all_shapes.iter().flatten().map(|v| v.maxx).max().unwrap() as usize + 1;
+        let max_shape_h = all_shapes.iter().flatten().map(|v| v.maxy).max().unwrap() as usize + 1;
+        (max_shape_w, max_shape_h)
+    }
 
-        let mut space = TreeSpace::new(tree.shape);
-        // a present is modeled by its coordinates+ its variant as (present_idx, variant_idx)
-        let mut options_used: Vec<Option<(u8, u8)>> =
-            Vec::with_capacity(tree.shape.x as usize * tree.shape.y as usize);
+    /// Every present in its own box, row by row.
+    fn box_grid_placements(tree: &Tree, all_shapes: &[Vec<Present>]) -> Vec<Placement> {
+        let (max_shape_w, max_shape_h) = Self::max_shape_size(all_shapes);
+        let boxes_per_row = tree.shape.x as usize / max_shape_w;
+        tree.presents
+            .iter()
+            .enumerate()
+            .flat_map(|(piece, count)| std::iter::repeat_n(piece, *count))
+            .enumerate()
+            .map(|(i, piece)| Placement {
+                piece,
+                variant: 0,
+                x: i % boxes_per_row * max_shape_w,
+                y: i / boxes_per_row * max_shape_h,
+            })
+            .collect()
+    }
 
-        let mut current_option: Option<(u8, u8)> = None;
-        let mut current_pos: Point = Point { x: 0, y: 0 };
-        'main: loop {
-            if missing_presents.iter().all(|m| *m == 0) {
-                return true;
-            }
-            let empty_places = (tree.shape.y as usize - current_pos.y as usize + 1)
-                * tree.shape.x as usize
-                - current_pos.x as usize;
-            if empty_places
-                >= missing_presents
+    fn search(tree: &Tree, all_shapes: &[Vec<Present>]) -> Option<Vec<Placement>> {
+        let pieces: Vec<Vec<Shape>> = all_shapes
+            .iter()
+            .map(|variants| {
+                variants
                     .iter()
-                    .enumerate()
-                    .map(|(pre_idx, count)| all_shapes[pre_idx][0].occupied.len() * *count)
-                    .sum()
-            {
-                // next shape
-                current_option =
-                    Self::next_available_shape(&current_option, &missing_presents, &variant_counts);
-                if let Some((present_idx, variant_idx)) = current_option {
-                    if space
-                        .place_present(
-                            &all_shapes[present_idx as usize][variant_idx as usize],
-                            &current_pos,
-                        )
-                        .is_ok()
-                    {
-                        assert!(missing_presents[present_idx as usize] > 0);
-                        missing_presents[present_idx as usize] -= 1;
-                    } else {
-                        // let's try next_shape
-                        continue;
-                    }
-                }
-                //  moving to the next point, all options tested here
-                if let Some(new_pos) = space.next_pos(current_pos) {
-                    options_used.push(current_option);
-                    current_pos = new_pos;
-                    current_option = None;
-                    continue;
-                }
-            }
-            //backtrack
-            while !options_used.is_empty() {
-                current_pos = space.prev_pos(current_pos).unwrap();
-                let last_pres = options_used.pop().unwrap();
-
-                if let Some((prev_pres, prev_var)) = last_pres {
-                    //let's try the shape after the one used in the previous attempt
-                    space.remove_present(
-                        &all_shapes[prev_pres as usize][prev_var as usize],
-                        &current_pos,
-                    );
-                    missing_presents[prev_pres as usize] += 1;
-                    current_option = Some((prev_pres, prev_var));
-
-                    continue 'main;
-                }
+                    .map(|v| {
+                        v.occupied
+                            .iter()
+                            .map(|p| (p.x as usize, p.y as usize))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Packing::new(
+            tree.shape.x as usize,
+            tree.shape.y as usize,
+            &pieces,
+            &tree.presents,
+        )
+        .solve()
+    }
+
+    /// Checks whether the presents fit under `tree`, and where to put them.
+    ///
+    /// Heuristics decide most trees; with `exact` the search always runs.
+    fn arrange_under_tree(tree: &Tree, all_shapes: &[Vec<Present>], exact: bool) -> Decision {
+        let start = Instant::now();
+        let heuristic = Self::heuristic(tree, all_shapes);
+        let (rule, skipped) = match heuristic {
+            Some(rule) if !exact => (rule, None),
+            _ => (Rule::Search, heuristic),
+        };
+        let placements = match rule {
+            Rule::BoxGrid => Some(Self::box_grid_placements(tree, all_shapes)),
+            Rule::AreaTooSmall => None,
+            Rule::Search => Self::search(tree, all_shapes),
+        };
+        Decision {
+            rule,
+            skipped,
+            placements,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    /// Draws the area under `tree`, one letter per placed present and `.` for
+    /// free cells. Overlapping cells, which would be a bug, are drawn as `#`.
+    fn render(tree: &Tree, placements: &[Placement], all_shapes: &[Vec<Present>]) -> String {
+        const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+        let width = tree.shape.x as usize;
+        let mut grid = vec![b'.'; width * tree.shape.y as usize];
+        for (i, placement) in placements.iter().enumerate() {
+            let letter = LETTERS[i % LETTERS.len()];
+            for p in &all_shapes[placement.piece][placement.variant].occupied {
+                let cell =
+                    &mut grid[(placement.y + p.y as usize) * width + placement.x + p.x as usize];
+                *cell = if *cell == b'.' { letter } else { b'#' };
             }
+        }
+        grid.chunks(width)
+            .map(|row| String::from_utf8_lossy(row))
+            .join("\n")
+    }
 
-            println!("no match for {tree:?}");
-            return false;
+    fn report(
+        i: usize,
+        tree: &Tree,
+        decision: &Decision,
+        options: Options,
+        all_shapes: &[Vec<Present>],
+    ) {
+        let (w, h) = (tree.shape.x, tree.shape.y);
+        if options.explain {
+            let verdict = if decision.fits() { "fits" } else { "no fit" };
+            let skipped = match decision.skipped {
+                Some(rule) if decision.disagrees() => format!(", DISAGREES with {rule:?}"),
+                Some(rule) => format!(", agrees with {rule:?}"),
+                None => String::new(),
+            };
+            eprintln!(
+                "tree {i} ({w}x{h}): {verdict} by {:?} in {:.1?}{skipped}",
+                decision.rule, decision.elapsed
+            );
+        }
+        if options.render {
+            match &decision.placements {
+                Some(placements) => eprintln!(
+                    "tree {i} ({w}x{h}):\n{}\n",
+                    Self::render(tree, placements, all_shapes)
+                ),
+                None => eprintln!("tree {i} ({w}x{h}): no fit\n"),
+            }
         }
     }
-    fn presents_fit_count(&self) -> usize {
-        let all_shapes = self
-            .presents
+
+    fn all_shapes(&self) -> Vec<Vec<Present>> {
+        self.presents
             .iter()
             .map(|p| p.all_presentations())
-            .collect_vec();
+            .collect_vec()
+    }
 
+    /// Trees are independent: they are checked in parallel, decisions are in order.
+    fn decisions(&self, all_shapes: &[Vec<Present>], exact: bool) -> Vec<Decision> {
         self.trees
+            .par_iter()
+            .map(|tree| Self::arrange_under_tree(tree, all_shapes, exact))
+            .collect()
+    }
+
+    fn presents_fit_count(&self, exact: bool) -> usize {
+        self.decisions(&self.all_shapes(), exact)
             .iter()
-            .filter(|tree| self.presents_fit_under_tree(tree, &all_shapes))
+            .filter(|d| d.fits())
             .count()
     }
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
     let cavern = Cavern::from_str(input).unwrap();
-    Some(cavern.presents_fit_count() as u64)
+    Some(cavern.presents_fit_count(false) as u64)
+}
+
+/// Diagnostics of part one, e.g. `cargo run --bin 12 -- --explain --render`.
+/// With `--exact`, the trees are decided by the full search.
+fn report(input: &str, flags: &Flags) -> Result<(), Box<dyn Error>> {
+    let options = Options::from_flags(flags);
+    if !options.exact && !options.explain && !options.render {
+        return Ok(());
+    }
+    let cavern = Cavern::from_str(input)?;
+    let all_shapes = cavern.all_shapes();
+    let decisions = cavern.decisions(&all_shapes, options.exact);
+    for (i, (tree, decision)) in cavern.trees.iter().zip(&decisions).enumerate() {
+        Cavern::report(i, tree, decision, options, &all_shapes);
+    }
+    if options.exact {
+        let fits = decisions.iter().filter(|d| d.fits()).count();
+        eprintln!("{fits} trees fit by the full search");
+    }
+    Ok(())
 }
 
 pub fn part_two(_input: &str) -> Option<u64> {
@@ -376,4 +416,58 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2));
     }
+
+    #[test]
+    fn test_exact_agrees_with_heuristics() {
+        let cavern =
+            Cavern::from_str(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(cavern.presents_fit_count(true), 2);
+
+        let all_shapes = cavern
+            .presents
+            .iter()
+            .map(|p| p.all_presentations())
+            .collect_vec();
+        // two boxes side by side
+        let tree = Tree {
+            shape: Point { x: 6, y: 3 },
+            presents: vec![1, 0, 0, 0, 1, 0],
+        };
+        let decision = Cavern::arrange_under_tree(&tree, &all_shapes, false);
+        assert_eq!((decision.rule, decision.skipped), (Rule::BoxGrid, None));
+        let decision = Cavern::arrange_under_tree(&tree, &all_shapes, true);
+        assert_eq!(
+            (decision.rule, decision.skipped),
+            (Rule::Search, Some(Rule::BoxGrid))
+        );
+        assert!(decision.fits() && !decision.disagrees());
+    }
+
+    #[test]
+    fn test_render() {
+        let cavern =
+            Cavern::from_str(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let all_shapes = cavern
+            .presents
+            .iter()
+            .map(|p| p.all_presentations())
+            .collect_vec();
+        for tree in &cavern.trees[..2] {
+            let decision = Cavern::arrange_under_tree(tree, &all_shapes, false);
+            let placements = decision.placements.unwrap();
+            assert_eq!(placements.len(), tree.presents.iter().sum::<usize>());
+
+            let grid = Cavern::render(tree, &placements, &all_shapes);
+            assert_eq!(grid.lines().count(), tree.shape.y as usize);
+            assert!(!grid.contains('#'), "overlapping presents:\n{grid}");
+            let occupied: usize = placements
+                .iter()
+                .map(|p| all_shapes[p.piece][p.variant].occupied.len())
+                .sum();
+            assert_eq!(
+                grid.chars().filter(char::is_ascii_alphabetic).count(),
+                occupied
+            );
+        }
+    }
 }
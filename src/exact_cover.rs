@@ -0,0 +1,274 @@
+/// Exact cover solver, using Knuth's Algorithm X with dancing links.
+///
+/// Primary columns must be covered exactly once. Secondary (optional) columns
+/// may be covered at most once, which models e.g. cells allowed to stay empty.
+use std::ops::ControlFlow;
+
+#[derive(Debug, Clone)]
+pub struct ExactCover {
+    primary: usize,
+    columns: usize,
+    rows: Vec<Vec<usize>>,
+}
+
+impl ExactCover {
+    /// Creates a problem with columns `0..primary` mandatory and the next
+    /// `secondary` ones optional.
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        Self {
+            primary,
+            columns: primary + secondary,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row covering the given columns, and returns its index.
+    pub fn add_row(&mut self, columns: impl IntoIterator<Item = usize>) -> usize {
+        let row: Vec<usize> = columns.into_iter().collect();
+        assert!(
+            !row.is_empty() && row.iter().all(|c| *c < self.columns),
+            "empty row or column out of range in {row:?}"
+        );
+        self.rows.push(row);
+        self.rows.len() - 1
+    }
+
+    pub fn row(&self, index: usize) -> &[usize] {
+        &self.rows[index]
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Calls `visit` with the row indexes of every solution, until it breaks.
+    pub fn for_each_solution(&self, mut visit: impl FnMut(&[usize]) -> ControlFlow<()>) {
+        let mut links = Links::new(self);
+        let mut solution = Vec::new();
+        let _ = links.search(&mut solution, &mut visit);
+    }
+
+    /// Returns the row indexes of the first solution found, if any.
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        let mut found = None;
+        self.for_each_solution(|rows| {
+            found = Some(rows.to_vec());
+            ControlFlow::Break(())
+        });
+        found
+    }
+
+    pub fn count_solutions(&self) -> usize {
+        let mut count = 0;
+        self.for_each_solution(|_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        count
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The toroidal doubly-linked lists of Algorithm X.
+///
+/// Node `0` is the root, nodes `1..=columns` are the column headers and the
+/// remaining nodes are the 1s of the matrix, row by row.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new(problem: &ExactCover) -> Self {
+        let headers = problem.columns + 1;
+        let capacity = headers + problem.rows.iter().map(Vec::len).sum::<usize>();
+        let mut links = Self {
+            left: Vec::with_capacity(capacity),
+            right: Vec::with_capacity(capacity),
+            up: Vec::with_capacity(capacity),
+            down: Vec::with_capacity(capacity),
+            column: Vec::with_capacity(capacity),
+            row: Vec::with_capacity(capacity),
+            size: vec![0; headers],
+        };
+
+        for node in 0..headers {
+            // only the primary columns are reachable from the root.
+            let (left, right) = match node {
+                0 => (problem.primary, if problem.primary > 0 { 1 } else { 0 }),
+                n if n <= problem.primary => (n - 1, if n == problem.primary { 0 } else { n + 1 }),
+                n => (n, n),
+            };
+            links.left.push(left);
+            links.right.push(right);
+            links.up.push(node);
+            links.down.push(node);
+            links.column.push(node);
+            links.row.push(usize::MAX);
+        }
+
+        for (r, columns) in problem.rows.iter().enumerate() {
+            let first = links.left.len();
+            for (i, c) in columns.iter().enumerate() {
+                let node = first + i;
+                let header = c + 1;
+                links.left.push(if i == 0 {
+                    first + columns.len() - 1
+                } else {
+                    node - 1
+                });
+                links.right.push(if i + 1 == columns.len() {
+                    first
+                } else {
+                    node + 1
+                });
+                links.up.push(links.up[header]);
+                links.down.push(header);
+                links.column.push(header);
+                links.row.push(r);
+
+                let last = links.up[header];
+                links.down[last] = node;
+                links.up[header] = node;
+                links.size[header] += 1;
+            }
+        }
+
+        links
+    }
+
+    fn cover(&mut self, c: usize) {
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = c;
+        self.left[r] = c;
+    }
+
+    fn search(
+        &mut self,
+        solution: &mut Vec<usize>,
+        visit: &mut impl FnMut(&[usize]) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        if self.right[0] == 0 {
+            return visit(solution);
+        }
+
+        // choose the primary column with the fewest candidate rows.
+        let mut c = self.right[0];
+        let mut j = self.right[c];
+        while j != 0 {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+            j = self.right[j];
+        }
+        if self.size[c] == 0 {
+            return ControlFlow::Continue(());
+        }
+
+        self.cover(c);
+        let mut r = self.down[c];
+        let mut flow = ControlFlow::Continue(());
+        while r != c && flow.is_continue() {
+            solution.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+
+            flow = self.search(solution, visit);
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            solution.pop();
+            r = self.down[r];
+        }
+        self.uncover(c);
+
+        flow
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::ExactCover;
+
+    #[test]
+    fn solves_knuth_example() {
+        // columns A..G, rows from "Dancing Links" (Knuth, 2000).
+        let mut problem = ExactCover::new(7, 0);
+        for row in [
+            vec![2, 4, 5],
+            vec![0, 3, 6],
+            vec![1, 2, 5],
+            vec![0, 3],
+            vec![1, 6],
+            vec![3, 4, 6],
+        ] {
+            problem.add_row(row);
+        }
+
+        let mut solution = problem.solve().unwrap();
+        solution.sort_unstable();
+        assert_eq!(solution, vec![0, 3, 4]);
+        assert_eq!(problem.count_solutions(), 1);
+    }
+
+    #[test]
+    fn allows_uncovered_secondary_columns() {
+        // one primary column, two optional ones.
+        let mut problem = ExactCover::new(1, 2);
+        problem.add_row([0, 1]);
+        problem.add_row([0, 2]);
+        problem.add_row([1, 2]);
+        assert_eq!(problem.count_solutions(), 2);
+
+        let mut problem = ExactCover::new(2, 1);
+        problem.add_row([0, 2]);
+        problem.add_row([1, 2]);
+        assert_eq!(problem.solve(), None);
+    }
+}
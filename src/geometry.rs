@@ -0,0 +1,279 @@
+/// Geometry helpers for rectilinear (orthogonal) polygons drawn on a tile grid.
+///
+/// Vertices are tiles, edges are the straight runs of tiles between consecutive
+/// vertices. A tile is "in" the polygon if it lies on an edge or inside it.
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Display;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A compressed axis: every vertex coordinate gets its own slot, and so does
+/// every non-empty gap between two consecutive vertex coordinates.
+#[derive(Debug, Clone)]
+struct Axis {
+    /// Inclusive `(first, last)` coordinates covered by each slot, in order.
+    spans: Vec<(i64, i64)>,
+}
+
+impl Axis {
+    fn new(mut coords: Vec<i64>) -> Self {
+        coords.sort_unstable();
+        coords.dedup();
+        let mut spans = Vec::with_capacity(2 * coords.len());
+        for (i, c) in coords.iter().enumerate() {
+            spans.push((*c, *c));
+            if let Some(next) = coords.get(i + 1)
+                && *next > c + 1
+            {
+                spans.push((c + 1, next - 1));
+            }
+        }
+        Self { spans }
+    }
+
+    fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Slot containing `c`, if `c` lies between the first and last vertex coordinates.
+    fn slot(&self, c: i64) -> Option<usize> {
+        let i = self.spans.partition_point(|(_, last)| *last < c);
+        self.spans
+            .get(i)
+            .filter(|(first, _)| *first <= c)
+            .map(|_| i)
+    }
+}
+
+/// A rectilinear polygon rasterised on a compressed grid, with 2D prefix sums
+/// of the cells lying outside of it.
+#[derive(Debug, Clone)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Point>,
+    xs: Axis,
+    ys: Axis,
+    /// `outside[r * (xs.len() + 1) + c]` counts the outside cells in rows `..r` and columns `..c`.
+    outside: Vec<u32>,
+}
+
+impl RectilinearPolygon {
+    /// Builds the polygon from its vertices, in boundary order.
+    ///
+    /// # Errors
+    /// Fails if there are less than 4 vertices, if a vertex is repeated, or if two
+    /// consecutive vertices (including the last and the first) are not aligned
+    /// horizontally or vertically.
+    pub fn new(vertices: Vec<Point>) -> Result<Self, PolygonError> {
+        if vertices.len() < 4 {
+            return Err(PolygonError::TooFewVertices(vertices.len()));
+        }
+        let mut seen = HashSet::new();
+        if let Some(p) = vertices.iter().find(|p| !seen.insert(**p)) {
+            return Err(PolygonError::DuplicateVertex(*p));
+        }
+        let edges = || {
+            vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .map(|(a, b)| (*a, *b))
+        };
+        if let Some((a, b)) = edges().find(|(a, b)| a.x != b.x && a.y != b.y) {
+            return Err(PolygonError::NotRectilinear(a, b));
+        }
+
+        let xs = Axis::new(vertices.iter().map(|p| p.x).collect());
+        let ys = Axis::new(vertices.iter().map(|p| p.y).collect());
+        let (width, height) = (xs.len(), ys.len());
+        let slot = |p: &Point| (xs.slot(p.x).unwrap(), ys.slot(p.y).unwrap());
+
+        let mut inside = vec![false; width * height];
+
+        // boundary
+        for (a, b) in edges() {
+            let ((ax, ay), (bx, by)) = (slot(&a), slot(&b));
+            for y in ay.min(by)..=ay.max(by) {
+                for x in ax.min(bx)..=ax.max(bx) {
+                    inside[y * width + x] = true;
+                }
+            }
+        }
+
+        // interior, by ray casting from the left with half-open vertical edges
+        let vertical_edges: Vec<(usize, i64, i64)> = edges()
+            .filter(|(a, b)| a.x == b.x)
+            .map(|(a, b)| (slot(&a).0, a.y.min(b.y), a.y.max(b.y)))
+            .collect();
+        for (row, (y, _)) in ys.spans.iter().enumerate() {
+            let mut crossings: Vec<usize> = vertical_edges
+                .iter()
+                .filter(|(_, top, bottom)| top <= y && y < bottom)
+                .map(|(col, _, _)| *col)
+                .collect();
+            crossings.sort_unstable();
+            for pair in crossings.chunks_exact(2) {
+                for col in pair[0]..pair[1] {
+                    inside[row * width + col] = true;
+                }
+            }
+        }
+
+        let mut outside = vec![0u32; (width + 1) * (height + 1)];
+        for y in 0..height {
+            for x in 0..width {
+                outside[(y + 1) * (width + 1) + x + 1] = u32::from(!inside[y * width + x])
+                    + outside[y * (width + 1) + x + 1]
+                    + outside[(y + 1) * (width + 1) + x]
+                    - outside[y * (width + 1) + x];
+            }
+        }
+
+        Ok(Self {
+            vertices,
+            xs,
+            ys,
+            outside,
+        })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Smallest and largest corners of the bounding box.
+    pub fn bounds(&self) -> (Point, Point) {
+        let (x0, x1) = (self.xs.spans[0].0, self.xs.spans[self.xs.len() - 1].1);
+        let (y0, y1) = (self.ys.spans[0].0, self.ys.spans[self.ys.len() - 1].1);
+        (Point::new(x0, y0), Point::new(x1, y1))
+    }
+
+    fn outside_count(&self, (x0, y0): (usize, usize), (x1, y1): (usize, usize)) -> u32 {
+        let w = self.xs.len() + 1;
+        self.outside[(y1 + 1) * w + x1 + 1] + self.outside[y0 * w + x0]
+            - self.outside[y0 * w + x1 + 1]
+            - self.outside[(y1 + 1) * w + x0]
+    }
+
+    /// Whether the tile `p` is on the boundary or inside the polygon.
+    pub fn contains(&self, p: Point) -> bool {
+        self.contains_rect(p, p)
+    }
+
+    /// Whether every tile of the axis-aligned rectangle with opposite corners
+    /// `a` and `b` (both included) is on the boundary or inside the polygon.
+    ///
+    /// Apart from locating the corners on the compressed axes, this is a
+    /// constant-time prefix-sum lookup.
+    pub fn contains_rect(&self, a: Point, b: Point) -> bool {
+        let slots = (
+            self.xs.slot(a.x.min(b.x)),
+            self.ys.slot(a.y.min(b.y)),
+            self.xs.slot(a.x.max(b.x)),
+            self.ys.slot(a.y.max(b.y)),
+        );
+        match slots {
+            (Some(x0), Some(y0), Some(x1), Some(y1)) => self.outside_count((x0, y0), (x1, y1)) == 0,
+            _ => false,
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error which can be returned when building a [`RectilinearPolygon`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolygonError {
+    TooFewVertices(usize),
+    DuplicateVertex(Point),
+    NotRectilinear(Point, Point),
+}
+
+impl Error for PolygonError {}
+
+impl Display for PolygonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolygonError::TooFewVertices(count) => {
+                write!(f, "expecting at least 4 vertices, got {count}")
+            }
+            PolygonError::DuplicateVertex(p) => write!(f, "vertex {},{} is repeated", p.x, p.y),
+            PolygonError::NotRectilinear(a, b) => write!(
+                f,
+                "edge from {},{} to {},{} is neither horizontal nor vertical",
+                a.x, a.y, b.x, b.y
+            ),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, PolygonError, RectilinearPolygon};
+
+    /// An L shape, with the notch at the bottom right (x > 5 and y > 5 are outside).
+    fn l_shape() -> RectilinearPolygon {
+        RectilinearPolygon::new(
+            [(0, 0), (10, 0), (10, 5), (5, 5), (5, 10), (0, 10)]
+                .map(|(x, y)| Point::new(x, y))
+                .to_vec(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn contains_tiles() {
+        let polygon = l_shape();
+        assert!(polygon.contains(Point::new(0, 0)));
+        assert!(polygon.contains(Point::new(7, 5)));
+        assert!(polygon.contains(Point::new(3, 8)));
+        assert!(!polygon.contains(Point::new(6, 6)));
+        assert!(!polygon.contains(Point::new(11, 0)));
+        assert!(!polygon.contains(Point::new(-1, 3)));
+    }
+
+    #[test]
+    fn contains_rectangles() {
+        let polygon = l_shape();
+        assert!(polygon.contains_rect(Point::new(0, 0), Point::new(10, 5)));
+        assert!(polygon.contains_rect(Point::new(5, 10), Point::new(0, 0)));
+        assert!(!polygon.contains_rect(Point::new(0, 0), Point::new(6, 6)));
+        assert!(!polygon.contains_rect(Point::new(0, 0), Point::new(10, 10)));
+    }
+
+    #[test]
+    fn rejects_diagonal_edges() {
+        let err = RectilinearPolygon::new(
+            [(0, 0), (4, 0), (4, 4), (1, 3)]
+                .map(|(x, y)| Point::new(x, y))
+                .to_vec(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            PolygonError::NotRectilinear(Point::new(4, 4), Point::new(1, 3))
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_vertices() {
+        let err = RectilinearPolygon::new(
+            [(0, 0), (4, 0), (4, 4), (4, 0), (0, 4)]
+                .map(|(x, y)| Point::new(x, y))
+                .to_vec(),
+        )
+        .unwrap_err();
+        assert_eq!(err, PolygonError::DuplicateVertex(Point::new(4, 0)));
+    }
+}
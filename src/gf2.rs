@@ -0,0 +1,277 @@
+/// Linear algebra over GF(2), on bit-packed vectors and matrices.
+///
+/// Typical use: "toggle" puzzles, where pressing a button flips a set of lights
+/// and pressing it twice is a no-op, are linear systems `A·x = b` over GF(2).
+use std::ops::BitXorAssign;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-length vector of bits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVector {
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit {i} out of range {}", self.len);
+        self.words[i / WORD_BITS] >> (i % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "bit {i} out of range {}", self.len);
+        let mask = 1 << (i % WORD_BITS);
+        if value {
+            self.words[i / WORD_BITS] |= mask;
+        } else {
+            self.words[i / WORD_BITS] &= !mask;
+        }
+    }
+
+    pub fn flip(&mut self, i: usize) {
+        assert!(i < self.len, "bit {i} out of range {}", self.len);
+        self.words[i / WORD_BITS] ^= 1 << (i % WORD_BITS);
+    }
+
+    /// Number of bits set, i.e. the Hamming weight.
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    /// Indexes of the bits set, in increasing order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|i| self.get(*i))
+    }
+}
+
+impl FromIterator<bool> for BitVector {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        let mut vector = Self::zeros(bits.len());
+        for (i, _) in bits.iter().enumerate().filter(|(_, b)| **b) {
+            vector.set(i, true);
+        }
+        vector
+    }
+}
+
+impl BitXorAssign<&BitVector> for BitVector {
+    fn bitxor_assign(&mut self, rhs: &BitVector) {
+        assert_eq!(self.len, rhs.len, "xor of vectors of different lengths");
+        for (w, r) in self.words.iter_mut().zip(&rhs.words) {
+            *w ^= r;
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A matrix over GF(2), stored as bit-packed rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    cols: usize,
+    rows: Vec<BitVector>,
+}
+
+impl BitMatrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            cols,
+            rows: vec![BitVector::zeros(cols); rows],
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn col_count(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.rows[row].get(col)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        self.rows[row].set(col, value);
+    }
+
+    /// Solves `self · x = rhs` by Gaussian elimination.
+    ///
+    /// Returns [`None`] if the system is inconsistent.
+    pub fn solve(&self, rhs: &BitVector) -> Option<SolutionSpace> {
+        assert_eq!(rhs.len(), self.rows.len(), "rhs length must match rows");
+
+        // augment every row with its rhs bit in an extra last column.
+        let mut rows: Vec<BitVector> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let mut augmented = BitVector::zeros(self.cols + 1);
+                for c in row.ones() {
+                    augmented.set(c, true);
+                }
+                augmented.set(self.cols, rhs.get(r));
+                augmented
+            })
+            .collect();
+
+        // reduced row echelon form; `pivots[i]` is the pivot column of row `i`.
+        let mut pivots: Vec<usize> = Vec::new();
+        for col in 0..self.cols {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..rows.len()).find(|r| rows[*r].get(col)) else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+            let (above, below) = rows.split_at_mut(rank);
+            let (pivot_row, below) = below.split_first_mut().unwrap();
+            for row in above.iter_mut().chain(below) {
+                if row.get(col) {
+                    *row ^= pivot_row;
+                }
+            }
+            pivots.push(col);
+        }
+
+        // a remaining `0 = 1` row means there is no solution.
+        if rows[pivots.len()..].iter().any(|row| row.get(self.cols)) {
+            return None;
+        }
+
+        let mut particular = BitVector::zeros(self.cols);
+        for (row, col) in pivots.iter().enumerate() {
+            particular.set(*col, rows[row].get(self.cols));
+        }
+
+        let null_space = (0..self.cols)
+            .filter(|col| !pivots.contains(col))
+            .map(|free| {
+                let mut basis = BitVector::zeros(self.cols);
+                basis.set(free, true);
+                for (row, col) in pivots.iter().enumerate() {
+                    basis.set(*col, rows[row].get(free));
+                }
+                basis
+            })
+            .collect();
+
+        Some(SolutionSpace {
+            particular,
+            null_space,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// All the solutions of a linear system: a particular solution plus any
+/// combination of the null space basis.
+#[derive(Debug, Clone)]
+pub struct SolutionSpace {
+    pub particular: BitVector,
+    pub null_space: Vec<BitVector>,
+}
+
+impl SolutionSpace {
+    /// Number of solutions, i.e. `2^dim(null space)`.
+    ///
+    /// # Panics
+    /// Panics if the null space has 128 dimensions or more.
+    pub fn count(&self) -> u128 {
+        1u128
+            .checked_shl(self.null_space.len() as u32)
+            .expect("null space too large to count")
+    }
+
+    /// Every solution, walking the null space in Gray code order so that each
+    /// step costs a single vector xor.
+    pub fn solutions(&self) -> impl Iterator<Item = BitVector> + '_ {
+        let mut current = self.particular.clone();
+        (0..self.count()).map(move |i| {
+            if i > 0 {
+                current ^= &self.null_space[i.trailing_zeros() as usize];
+            }
+            current.clone()
+        })
+    }
+
+    /// A solution with the fewest bits set.
+    ///
+    /// Exhaustive over the null space: only use it when its dimension is small.
+    pub fn min_weight(&self) -> BitVector {
+        self.solutions().min_by_key(BitVector::count_ones).unwrap()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::{BitMatrix, BitVector};
+
+    fn matrix(rows: &[&str]) -> BitMatrix {
+        let mut matrix = BitMatrix::zeros(rows.len(), rows[0].len());
+        for (r, row) in rows.iter().enumerate() {
+            for (c, _) in row.chars().enumerate().filter(|(_, b)| *b == '1') {
+                matrix.set(r, c, true);
+            }
+        }
+        matrix
+    }
+
+    fn vector(bits: &str) -> BitVector {
+        bits.chars().map(|b| b == '1').collect()
+    }
+
+    #[test]
+    fn solves_systems() {
+        let a = matrix(&["110", "011", "101"]);
+        let space = a.solve(&vector("110")).unwrap();
+        assert_eq!(space.count(), 2);
+        for x in space.solutions() {
+            let product: BitVector = (0..3)
+                .map(|r| x.ones().filter(|c| a.get(r, *c)).count() % 2 == 1)
+                .collect();
+            assert_eq!(product, vector("110"));
+        }
+
+        assert!(a.solve(&vector("100")).is_none());
+    }
+
+    #[test]
+    fn finds_min_weight_solutions() {
+        // lights `.##.` with buttons (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let a = matrix(&["000011", "010001", "001110", "110100"]);
+        let space = a.solve(&vector("0110")).unwrap();
+        assert_eq!(space.min_weight().count_ones(), 2);
+    }
+
+    #[test]
+    fn packs_bits_across_words() {
+        let mut v = BitVector::zeros(130);
+        v.set(0, true);
+        v.set(64, true);
+        v.set(129, true);
+        v.flip(64);
+        assert_eq!(v.ones().collect::<Vec<_>>(), vec![0, 129]);
+    }
+}
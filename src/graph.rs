@@ -0,0 +1,351 @@
+/// Directed graph helpers: topological sort and memoized path counting.
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// A directed graph over copyable node labels (e.g. `&str`).
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl<N: Copy + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            successors: Vec::new(),
+        }
+    }
+}
+
+impl<N: Copy + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of `node`, inserting it if it's not known yet.
+    fn insert(&mut self, node: N) -> usize {
+        *self.index.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.successors.push(Vec::new());
+            self.nodes.len() - 1
+        })
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.insert(node);
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.insert(from);
+        let to = self.insert(to);
+        self.successors[from].push(to);
+    }
+
+    pub fn contains(&self, node: N) -> bool {
+        self.index.contains_key(&node)
+    }
+
+    /// All nodes, in insertion order.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn successors(&self, node: N) -> impl Iterator<Item = N> + '_ {
+        self.index
+            .get(&node)
+            .into_iter()
+            .flat_map(|i| self.successors[*i].iter().map(|s| self.nodes[*s]))
+    }
+
+    /// Nodes reachable from `start` (itself included), in depth-first order.
+    pub fn reachable_from(&self, start: N) -> Vec<N> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut reached = Vec::new();
+        let mut stack: Vec<usize> = self.index.get(&start).copied().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut seen[node], true) {
+                continue;
+            }
+            reached.push(self.nodes[node]);
+            stack.extend(self.successors[node].iter().rev().filter(|s| !seen[**s]));
+        }
+        reached
+    }
+
+    /// Sorts the nodes so that every edge goes from an earlier to a later node.
+    ///
+    /// # Errors
+    /// Returns a [`CycleError`] holding one of the cycles if the graph is not acyclic.
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        Ok(self
+            .topological_order()?
+            .into_iter()
+            .map(|i| self.nodes[i])
+            .collect())
+    }
+
+    /// Kahn's algorithm over node indexes.
+    fn topological_order(&self) -> Result<Vec<usize>, CycleError<N>> {
+        let mut in_degrees = vec![0usize; self.nodes.len()];
+        for s in self.successors.iter().flatten() {
+            in_degrees[*s] += 1;
+        }
+
+        let mut order: Vec<usize> = (0..self.nodes.len())
+            .filter(|i| in_degrees[*i] == 0)
+            .collect();
+        let mut next = 0;
+        while next < order.len() {
+            for s in &self.successors[order[next]] {
+                in_degrees[*s] -= 1;
+                if in_degrees[*s] == 0 {
+                    order.push(*s);
+                }
+            }
+            next += 1;
+        }
+
+        if order.len() == self.nodes.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&in_degrees))
+        }
+    }
+
+    /// Every node left with a positive in-degree after Kahn's algorithm has a
+    /// predecessor in the same situation, so walking predecessors must loop.
+    fn find_cycle(&self, in_degrees: &[usize]) -> CycleError<N> {
+        let mut predecessor = vec![None; self.nodes.len()];
+        for (from, outs) in self.successors.iter().enumerate() {
+            for to in outs {
+                if in_degrees[from] > 0 && in_degrees[*to] > 0 {
+                    predecessor[*to] = Some(from);
+                }
+            }
+        }
+
+        let mut visited = vec![false; self.nodes.len()];
+        let mut current = in_degrees.iter().position(|d| *d > 0).unwrap();
+        while !visited[current] {
+            visited[current] = true;
+            current = predecessor[current].unwrap();
+        }
+
+        let start = current;
+        let mut cycle = vec![self.nodes[start]];
+        current = predecessor[start].unwrap();
+        while current != start {
+            cycle.push(self.nodes[current]);
+            current = predecessor[current].unwrap();
+        }
+        cycle.reverse();
+
+        CycleError { cycle }
+    }
+
+    /// Checks that the graph is acyclic, which enables path counting.
+    ///
+    /// # Errors
+    /// Returns a [`CycleError`] if the graph contains a cycle.
+    pub fn into_dag(self) -> Result<Dag<N>, CycleError<N>> {
+        let order = self.topological_order()?;
+        let mut rank = vec![0; order.len()];
+        for (r, i) in order.iter().enumerate() {
+            rank[*i] = r;
+        }
+        Ok(Dag {
+            graph: self,
+            order,
+            rank,
+            paths_to: RefCell::new(HashMap::new()),
+        })
+    }
+}
+
+impl<N: Copy + Eq + Hash> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<T: IntoIterator<Item = (N, N)>>(iter: T) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in iter {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An acyclic [`Graph`], with path counts memoized per target node.
+#[derive(Debug)]
+pub struct Dag<N> {
+    graph: Graph<N>,
+    order: Vec<usize>,
+    rank: Vec<usize>,
+    paths_to: RefCell<HashMap<usize, Vec<u64>>>,
+}
+
+impl<N: Copy + Eq + Hash> Dag<N> {
+    pub fn graph(&self) -> &Graph<N> {
+        &self.graph
+    }
+
+    /// Nodes in topological order.
+    pub fn topological_order(&self) -> impl Iterator<Item = N> + '_ {
+        self.order.iter().map(|i| self.graph.nodes[*i])
+    }
+
+    /// Number of paths from every node to `target`, computed once per target.
+    fn with_paths_to<T>(&self, target: usize, f: impl FnOnce(&[u64]) -> T) -> T {
+        let mut memo = self.paths_to.borrow_mut();
+        let counts = memo.entry(target).or_insert_with(|| {
+            let mut counts = vec![0u64; self.order.len()];
+            counts[target] = 1;
+            for node in self.order.iter().rev().filter(|n| **n != target) {
+                counts[*node] = self.graph.successors[*node]
+                    .iter()
+                    .map(|s| counts[*s])
+                    .sum();
+            }
+            counts
+        });
+        f(counts)
+    }
+
+    /// Counts the distinct paths from `from` to `to`. Unknown nodes have no path.
+    pub fn count_paths(&self, from: N, to: N) -> u64 {
+        match (self.graph.index.get(&from), self.graph.index.get(&to)) {
+            (Some(from), Some(to)) => self.with_paths_to(*to, |counts| counts[*from]),
+            _ => 0,
+        }
+    }
+
+    /// Number of paths from every node to `to`, in [`Graph::nodes`] order.
+    pub fn count_paths_to(&self, to: N) -> Vec<u64> {
+        match self.graph.index.get(&to) {
+            Some(to) => self.with_paths_to(*to, <[u64]>::to_vec),
+            None => vec![0; self.graph.nodes.len()],
+        }
+    }
+
+    /// Counts the paths from `from` to `to` that visit every waypoint, in any order.
+    ///
+    /// In a DAG a path visits nodes by increasing topological rank, so the only
+    /// order that can contribute is the one sorted by rank.
+    pub fn count_paths_through(&self, from: N, to: N, waypoints: &[N]) -> u64 {
+        let mut stops = vec![from];
+        stops.extend(waypoints);
+        stops.push(to);
+
+        let Some(mut ranked) = stops
+            .iter()
+            .map(|n| self.graph.index.get(n).copied())
+            .collect::<Option<Vec<usize>>>()
+        else {
+            return 0;
+        };
+        let (first, last) = (ranked[0], ranked[ranked.len() - 1]);
+        ranked.sort_unstable_by_key(|i| self.rank[*i]);
+        if ranked[0] != first || ranked[ranked.len() - 1] != last {
+            return 0;
+        }
+
+        ranked
+            .windows(2)
+            .map(|w| self.with_paths_to(w[1], |counts| counts[w[0]]))
+            .product()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error returned when a graph expected to be acyclic contains a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    /// The nodes of one cycle, each one having an edge to the next (and the last to the first).
+    pub cycle: Vec<N>,
+}
+
+impl<N: Display> Display for CycleError<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph contains a cycle: ")?;
+        for node in &self.cycle {
+            write!(f, "{node} -> ")?;
+        }
+        match self.cycle.first() {
+            Some(node) => write!(f, "{node}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N: Debug + Display> Error for CycleError<N> {}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::Graph;
+
+    fn diamond() -> Graph<&'static str> {
+        [("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("d", "e")]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = diamond();
+        let order = graph.topological_sort().unwrap();
+        let pos = |n: &str| order.iter().position(|o| *o == n).unwrap();
+        for from in graph.nodes().iter().copied() {
+            for to in graph.successors(from) {
+                assert!(pos(from) < pos(to));
+            }
+        }
+    }
+
+    #[test]
+    fn finds_reachable_nodes() {
+        let graph = diamond();
+        assert_eq!(graph.reachable_from("b"), vec!["b", "d", "e"]);
+        assert_eq!(graph.reachable_from("a").len(), 5);
+        assert!(graph.reachable_from("z").is_empty());
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let mut graph = diamond();
+        graph.add_edge("e", "b");
+        let err = graph.topological_sort().unwrap_err();
+        assert_eq!(err.cycle.len(), 3);
+        assert_eq!(
+            err.to_string().matches("->").count(),
+            3,
+            "unexpected message {err}"
+        );
+        assert!(graph.into_dag().is_err());
+    }
+
+    #[test]
+    fn counts_paths() {
+        let dag = diamond().into_dag().unwrap();
+        assert_eq!(dag.count_paths("a", "e"), 2);
+        assert_eq!(dag.count_paths("b", "e"), 1);
+        assert_eq!(dag.count_paths("e", "a"), 0);
+        assert_eq!(dag.count_paths("a", "unknown"), 0);
+    }
+
+    #[test]
+    fn counts_paths_through_waypoints() {
+        let dag = diamond().into_dag().unwrap();
+        assert_eq!(dag.count_paths_through("a", "e", &[]), 2);
+        assert_eq!(dag.count_paths_through("a", "e", &["d", "b"]), 1);
+        assert_eq!(dag.count_paths_through("a", "e", &["b", "c"]), 0);
+        assert_eq!(dag.count_paths_through("b", "e", &["a"]), 0);
+    }
+}
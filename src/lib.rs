@@ -1,3 +1,9 @@
+pub mod exact_cover;
+pub mod geometry;
+pub mod gf2;
+pub mod graph;
+pub mod polyomino;
 pub mod template;
+pub mod text_layout;
 
 // Use this file to add helper functions and additional modules.
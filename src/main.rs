@@ -1,13 +1,21 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+#[cfg(feature = "today")]
+use advent_of_code::template::commands::wait;
+use advent_of_code::template::commands::{
+    all, completions, download, init_year, read, scaffold, solve, time,
+};
 use args::{AppArguments, parse};
 
 #[cfg(feature = "today")]
 use advent_of_code::template::Day;
 #[cfg(feature = "today")]
+use advent_of_code::template::config::Config;
+#[cfg(feature = "today")]
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::input::InputSource;
+    use advent_of_code::template::style::ColorChoice;
+    use advent_of_code::template::{Day, DaySet, cli};
     use std::ffi::OsString;
     use std::process;
 
@@ -24,24 +32,34 @@ mod args {
             overwrite: bool,
         },
         Solve {
-            day: Day,
+            days: DaySet,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            input: InputSource,
             extra_args: Vec<OsString>,
         },
         All {
+            days: DaySet,
             release: bool,
             extra_args: Vec<OsString>,
         },
         Time {
             all: bool,
-            day: Option<Day>,
+            days: Option<DaySet>,
             store: bool,
             extra_args: Vec<OsString>,
         },
         #[cfg(feature = "today")]
         Today,
+        #[cfg(feature = "today")]
+        Wait,
+        InitYear {
+            year: u16,
+        },
+        Completions {
+            shell: String,
+        },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
@@ -60,19 +78,39 @@ mod args {
             .collect();
 
         let mut args = pico_args::Arguments::from_vec(main_args);
+        let help = args.contains(["-h", "--help"]);
+        // read by the styling layer, only validated here.
+        let _: Option<ColorChoice> = args.opt_value_from_str("--color")?;
+        let subcommand = args.subcommand()?;
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-                extra_args,
-            },
+        if help || subcommand.as_deref() == Some("help") {
+            let topic = match subcommand.as_deref() {
+                Some("help") => args.opt_free_from_str::<String>()?,
+                other => other.map(str::to_string),
+            };
+            match topic.as_deref().and_then(cli::command) {
+                Some(command) => print!("{}", command.usage()),
+                None => print!("{}", cli::usage()),
+            }
+            process::exit(0);
+        }
+
+        let app_args = match subcommand.as_deref() {
+            Some("all") => {
+                let release = args.contains("--release");
+                AppArguments::All {
+                    days: args.opt_free_from_str()?.unwrap_or_else(DaySet::all),
+                    release,
+                    extra_args,
+                }
+            }
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
+                    days: args.opt_free_from_str()?,
                     store,
                     extra_args,
                 }
@@ -88,32 +126,71 @@ mod args {
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-                extra_args,
-            },
+            Some("solve") => {
+                let release = args.contains("--release");
+                let submit = args.opt_value_from_str("--submit")?;
+                let dhat = args.contains("--dhat");
+                let example_part = args.opt_value_from_fn("--example-part", parse_part)?;
+                let path: Option<String> = args.opt_value_from_str("--input")?;
+                let input = match (path, example_part, args.contains("--example")) {
+                    (Some(_), Some(_), _) | (Some(_), _, true) => {
+                        eprintln!("`--input` can't be combined with `--example`.");
+                        process::exit(1);
+                    }
+                    (Some(path), None, false) => InputSource::from_path(&path),
+                    (None, Some(part), _) => InputSource::Example { part: Some(part) },
+                    (None, None, true) => InputSource::Example { part: None },
+                    (None, None, false) => InputSource::Puzzle,
+                };
+                AppArguments::Solve {
+                    days: args.free_from_str()?,
+                    release,
+                    dhat,
+                    submit,
+                    input,
+                    extra_args,
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
+            #[cfg(feature = "today")]
+            Some("wait") => AppArguments::Wait,
+            Some("init-year") => AppArguments::InitYear {
+                year: args.free_from_str()?,
+            },
+            Some("completions") => AppArguments::Completions {
+                shell: args.free_from_str()?,
+            },
             Some(x) => {
-                eprintln!("Unknown command: {x}");
+                eprintln!("Unknown command: {x}\n\n{}", cli::usage());
                 process::exit(1);
             }
             None => {
-                eprintln!("No command specified.");
+                eprintln!("No command specified.\n\n{}", cli::usage());
                 process::exit(1);
             }
         };
 
         let remaining = args.finish();
         if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
+            let usage = subcommand
+                .as_deref()
+                .and_then(cli::command)
+                .map_or_else(cli::usage, cli::Command::usage);
+            eprintln!("Unknown argument(s): {remaining:?}\n\n{usage}");
+            process::exit(1);
         }
 
         Ok(app_args)
     }
+
+    fn parse_part(s: &str) -> Result<u8, &'static str> {
+        match s {
+            "1" => Ok(1),
+            "2" => Ok(2),
+            _ => Err("expecting part 1 or 2"),
+        }
+    }
 }
 
 fn main() {
@@ -124,15 +201,16 @@ fn main() {
         }
         Ok(args) => match args {
             AppArguments::All {
+                days,
                 release,
                 extra_args,
-            } => all::handle(release, &extra_args),
+            } => all::handle(&days, release, &extra_args),
             AppArguments::Time {
-                day,
+                days,
                 all,
                 store,
                 extra_args,
-            } => time::handle(day, all, store, &extra_args),
+            } => time::handle(days, all, store, &extra_args),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -146,15 +224,32 @@ fn main() {
                 }
             }
             AppArguments::Solve {
-                day,
+                days,
                 release,
                 dhat,
                 submit,
+                input,
                 extra_args,
-            } => solve::handle(day, release, dhat, submit, &extra_args),
+            } => match days.single_day() {
+                Some(_) if submit.is_some() && !input.is_puzzle() => {
+                    eprintln!("Answers are only submitted for the puzzle input.");
+                    std::process::exit(1);
+                }
+                Some(day) => solve::handle(day, release, dhat, submit, &input, &extra_args),
+                None if dhat || submit.is_some() || !input.is_puzzle() => {
+                    eprintln!("`--dhat`, `--submit`, `--example` and `--input` need a single day.");
+                    std::process::exit(1);
+                }
+                None => all::handle(&days, release, &extra_args),
+            },
+            AppArguments::InitYear { year } => init_year::handle(year),
+            AppArguments::Completions { shell } => completions::handle(&shell),
+            #[cfg(feature = "today")]
+            AppArguments::Wait => wait::handle(),
             #[cfg(feature = "today")]
             AppArguments::Today => {
-                match Day::today() {
+                let last_day = Config::get().last_day;
+                match Day::today(last_day) {
                     Some(day) => {
                         scaffold::handle(day, false);
                         download::handle(day);
@@ -162,8 +257,9 @@ fn main() {
                     }
                     None => {
                         eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
+                            "`today` command can only be run between december 1 and \
+                            december {}. Please use `scaffold` with a specific day.",
+                            last_day.into_inner()
                         );
                         process::exit(1)
                     }
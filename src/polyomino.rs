@@ -0,0 +1,308 @@
+/// Packing of polyominoes into a rectangular area.
+///
+/// The search fills the area cell by cell in reading order: the first free cell
+/// is either left empty (while there is room to spare) or covered by a piece
+/// whose own first cell lands on it. Copies of a piece are only counted, never
+/// told apart, so identical pieces are always placed in a single canonical
+/// order, and states already known to fail are memoized, within `MEMO_BYTES`.
+use std::collections::HashSet;
+
+/// Memory given to the failed states of a search, which is paid once per
+/// thread when packings are solved in parallel. Once it is used up, the search
+/// goes on without memoizing.
+const MEMO_BYTES: usize = 16 << 20;
+
+/// The cells of a piece variant, as `(x, y)` offsets from its top-left corner.
+pub type Shape = Vec<(usize, usize)>;
+
+/// A piece variant put at a given position of the area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub piece: usize,
+    pub variant: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Packing {
+    width: usize,
+    height: usize,
+    counts: Vec<usize>,
+    /// Number of cells of every piece.
+    sizes: Vec<usize>,
+    placements: Vec<Placement>,
+    /// The cells covered by every placement.
+    cells: Vec<Vec<usize>>,
+    /// Indexes of the placements covering a given cell.
+    covering: Vec<Vec<usize>>,
+    /// Indexes of the placements whose first cell, in reading order, is a given cell.
+    by_anchor: Vec<Vec<usize>>,
+}
+
+impl Packing {
+    /// `pieces[i]` lists the variants (rotations, flips...) of piece `i`, and
+    /// `counts[i]` is the number of copies of piece `i` that must be placed.
+    pub fn new(width: usize, height: usize, pieces: &[Vec<Shape>], counts: &[usize]) -> Self {
+        let mut placements = Vec::new();
+        let mut cells = Vec::new();
+        let mut by_anchor = vec![Vec::new(); width * height];
+        let mut covering = vec![Vec::new(); width * height];
+
+        for (piece, variants) in pieces.iter().enumerate() {
+            if counts.get(piece).is_none_or(|c| *c == 0) {
+                continue;
+            }
+            for (variant, shape) in variants.iter().enumerate() {
+                for (x, y) in Self::positions(width, height, shape) {
+                    let mut covered: Vec<usize> = shape
+                        .iter()
+                        .map(|(dx, dy)| (y + dy) * width + x + dx)
+                        .collect();
+                    covered.sort_unstable();
+                    by_anchor[covered[0]].push(placements.len());
+                    for c in &covered {
+                        covering[*c].push(placements.len());
+                    }
+                    placements.push(Placement {
+                        piece,
+                        variant,
+                        x,
+                        y,
+                    });
+                    cells.push(covered);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            counts: counts.to_vec(),
+            sizes: pieces
+                .iter()
+                .map(|variants| variants.first().map_or(0, Vec::len))
+                .collect(),
+            placements,
+            cells,
+            covering,
+            by_anchor,
+        }
+    }
+
+    /// Top-left positions where `shape` fits inside the area.
+    fn positions(
+        width: usize,
+        height: usize,
+        shape: &Shape,
+    ) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let shape_w = shape.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+        let shape_h = shape.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+        let xs = 0..(width + 1).saturating_sub(shape_w);
+        (0..(height + 1).saturating_sub(shape_h)).flat_map(move |y| xs.clone().map(move |x| (x, y)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every candidate placement.
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements
+    }
+
+    /// Returns one way to place every piece copy without overlaps, if any.
+    pub fn solve(&self) -> Option<Vec<Placement>> {
+        let area = self.width * self.height;
+        let needed: usize = self
+            .counts
+            .iter()
+            .zip(&self.sizes)
+            .map(|(c, s)| c * s)
+            .sum();
+        if needed > area {
+            return None;
+        }
+        if self.counts.iter().any(|c| *c > 0) && self.placements.is_empty() {
+            return None;
+        }
+
+        let key_len = area.div_ceil(64) + self.counts.len();
+        let mut search = Search {
+            packing: self,
+            decided: vec![0; area.div_ceil(64)],
+            counts: self.counts.clone(),
+            chosen: Vec::new(),
+            failed: HashSet::new(),
+            memo_limit: MEMO_BYTES / (size_of::<Vec<u64>>() + 8 * key_len),
+            key: Vec::with_capacity(key_len),
+        };
+        search
+            .run(0, needed, area - needed)
+            .then(|| search.chosen.iter().map(|p| self.placements[*p]).collect())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The mutable state of a [`Packing::solve`] call.
+struct Search<'p> {
+    packing: &'p Packing,
+    decided: Vec<u64>,
+    counts: Vec<usize>,
+    chosen: Vec<usize>,
+    /// Failed states, as keys written by [`Search::write_key`].
+    failed: HashSet<Vec<u64>>,
+    /// Number of failed states fitting in `MEMO_BYTES`.
+    memo_limit: usize,
+    /// Key of the current state, reused from node to node.
+    key: Vec<u64>,
+}
+
+impl Search<'_> {
+    fn is_decided(&self, cell: usize) -> bool {
+        self.decided[cell / 64] >> (cell % 64) & 1 == 1
+    }
+
+    fn toggle(&mut self, cell: usize) {
+        self.decided[cell / 64] ^= 1 << (cell % 64);
+    }
+
+    /// Writes the cells already decided (covered or left empty), then the
+    /// copies still to place, to `key`.
+    fn write_key(&mut self) {
+        self.key.clear();
+        self.key.extend_from_slice(&self.decided);
+        self.key.extend(self.counts.iter().map(|c| *c as u64));
+    }
+
+    fn is_known_failure(&mut self) -> bool {
+        self.write_key();
+        self.failed.contains(&self.key)
+    }
+
+    fn remember_failure(&mut self) {
+        if self.failed.len() < self.memo_limit {
+            self.write_key();
+            self.failed.insert(self.key.clone());
+        }
+    }
+
+    /// Counts the free cells from `from` on that no placement left can cover,
+    /// stopping once there are more than `limit` of them.
+    fn dead_cells(&self, from: usize, limit: usize) -> usize {
+        let packing = self.packing;
+        let area = packing.width * packing.height;
+        let mut dead = 0;
+        for cell in (from..area).filter(|c| !self.is_decided(*c)) {
+            let coverable = packing.covering[cell].iter().any(|p| {
+                self.counts[packing.placements[*p].piece] > 0
+                    && packing.cells[*p].iter().all(|c| !self.is_decided(*c))
+            });
+            if !coverable {
+                dead += 1;
+                if dead > limit {
+                    break;
+                }
+            }
+        }
+        dead
+    }
+
+    /// Fills the area from `cell` on, with `needed` cells still to cover by
+    /// pieces and `spare` cells that may be left empty.
+    fn run(&mut self, cell: usize, needed: usize, spare: usize) -> bool {
+        if needed == 0 {
+            return true;
+        }
+        let area = self.packing.width * self.packing.height;
+        let Some(cell) = (cell..area).find(|c| !self.is_decided(*c)) else {
+            return false;
+        };
+        if self.is_known_failure() {
+            return false;
+        }
+        if self.dead_cells(cell, spare) > spare {
+            self.remember_failure();
+            return false;
+        }
+
+        let packing = self.packing;
+        for p in &packing.by_anchor[cell] {
+            let piece = packing.placements[*p].piece;
+            let cells = &packing.cells[*p];
+            if self.counts[piece] == 0 || cells.iter().any(|c| self.is_decided(*c)) {
+                continue;
+            }
+            cells.iter().for_each(|c| self.toggle(*c));
+            self.counts[piece] -= 1;
+            self.chosen.push(*p);
+            if self.run(cell + 1, needed - cells.len(), spare) {
+                return true;
+            }
+            self.chosen.pop();
+            self.counts[piece] += 1;
+            cells.iter().for_each(|c| self.toggle(*c));
+        }
+
+        if spare > 0 {
+            self.toggle(cell);
+            let found = self.run(cell + 1, needed, spare - 1);
+            self.toggle(cell);
+            if found {
+                return true;
+            }
+        }
+
+        self.remember_failure();
+        false
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::Packing;
+
+    #[test]
+    fn packs_dominoes() {
+        let domino = vec![vec![(0, 0), (1, 0)], vec![(0, 0), (0, 1)]];
+        let pieces = [domino];
+        let packing = Packing::new(2, 2, &pieces, &[2]);
+        assert_eq!(packing.solve().map(|p| p.len()), Some(2));
+
+        let packing = Packing::new(3, 1, &pieces, &[2]);
+        assert_eq!(packing.solve(), None);
+    }
+
+    #[test]
+    fn leaves_cells_empty() {
+        let l_tromino = vec![vec![(0, 0), (0, 1), (1, 1)]];
+        let packing = Packing::new(3, 3, &[l_tromino], &[2]);
+        let solution = packing.solve().unwrap();
+        assert_eq!(solution.len(), 2);
+        assert_ne!(solution[0], solution[1]);
+    }
+
+    #[test]
+    fn places_identical_copies_once() {
+        let domino = vec![vec![(0, 0), (1, 0)], vec![(0, 0), (0, 1)]];
+        let packing = Packing::new(7, 3, &[domino], &[10]);
+        assert_eq!(packing.solve().map(|p| p.len()), Some(10));
+
+        // famously, a 3x3 square can't be tiled with L-trominoes
+        let l_tromino = vec![
+            vec![(0, 0), (0, 1), (1, 1)],
+            vec![(1, 0), (0, 1), (1, 1)],
+            vec![(0, 0), (1, 0), (0, 1)],
+            vec![(0, 0), (1, 0), (1, 1)],
+        ];
+        let packing = Packing::new(3, 3, &[l_tromino], &[3]);
+        assert_eq!(packing.solve(), None);
+    }
+}
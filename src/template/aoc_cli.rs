@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::template::Day;
+use crate::template::config::Config;
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -81,18 +82,15 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
 }
 
 fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+    Config::get().input_path(day).display().to_string()
 }
 
 fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+    Config::get().puzzle_path(day).display().to_string()
 }
 
 fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+    Config::get().year()
 }
 
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
@@ -0,0 +1,276 @@
+/// Description of the command line, shared by the argument parser, the
+/// `--help` output and the shell completions.
+use std::fmt::Write;
+
+/// A flag of a subcommand, e.g. `--submit <part>`.
+pub struct Flag {
+    pub name: &'static str,
+    /// Placeholder of the flag value, for flags taking one.
+    pub value: Option<&'static str>,
+    pub about: &'static str,
+}
+
+/// The positional argument of a subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    None,
+    Day,
+    /// A [`DaySet`](crate::template::DaySet).
+    Days,
+    OptionalDays,
+    Year,
+    /// One of a fixed list of words.
+    Choice(&'static str, &'static [&'static str]),
+}
+
+pub struct Command {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub operand: Operand,
+    pub flags: &'static [Flag],
+    /// Whether arguments after `--` are passed on to cargo.
+    pub extra_args: bool,
+}
+
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+const DAYS_USAGE: &str = "
+<days> is a comma separated list of:
+  7, 3-5, 10-, -4  days and ranges of days
+  all              every day
+  unsolved         scaffolded days without any stored timing
+  unbenched        scaffolded days with a part missing from the stored timings
+  slowest:<count>  the days with the largest stored timings
+";
+
+const RELEASE: Flag = Flag {
+    name: "--release",
+    value: None,
+    about: "build with optimizations",
+};
+
+/// Flags accepted by every subcommand.
+pub const GLOBAL_FLAGS: &[Flag] = &[Flag {
+    name: "--color",
+    value: Some("<when>"),
+    about: "use colors: auto (default, unless NO_COLOR is set), always or never",
+}];
+
+pub const COMMANDS: &[Command] = &[
+    Command {
+        name: "scaffold",
+        about: "Create the solution, input and example files of a day",
+        operand: Operand::Day,
+        flags: &[
+            Flag {
+                name: "--download",
+                value: None,
+                about: "also download the puzzle input and description",
+            },
+            Flag {
+                name: "--overwrite",
+                value: None,
+                about: "replace the files if they already exist",
+            },
+        ],
+        extra_args: false,
+    },
+    Command {
+        name: "download",
+        about: "Download the puzzle input and description of a day",
+        operand: Operand::Day,
+        flags: &[],
+        extra_args: false,
+    },
+    Command {
+        name: "read",
+        about: "Print the puzzle description of a day",
+        operand: Operand::Day,
+        flags: &[],
+        extra_args: false,
+    },
+    Command {
+        name: "solve",
+        about: "Run the solutions of some days against their input",
+        operand: Operand::Days,
+        flags: &[
+            RELEASE,
+            Flag {
+                name: "--dhat",
+                value: None,
+                about: "profile heap allocations with DHAT (single day only)",
+            },
+            Flag {
+                name: "--submit",
+                value: Some("<part>"),
+                about: "submit the answer of part 1 or 2 (single day only)",
+            },
+            Flag {
+                name: "--example",
+                value: None,
+                about: "run on the example instead of the puzzle input (single day only)",
+            },
+            Flag {
+                name: "--example-part",
+                value: Some("<part>"),
+                about: "run on the example of a part, e.g. `01-2.txt` (single day only)",
+            },
+            Flag {
+                name: "--input",
+                value: Some("<path>"),
+                about: "run on another input file, `-` for stdin (single day only)",
+            },
+        ],
+        extra_args: true,
+    },
+    Command {
+        name: "all",
+        about: "Run the solutions of every day, or of the selected days",
+        operand: Operand::OptionalDays,
+        flags: &[RELEASE],
+        extra_args: true,
+    },
+    Command {
+        name: "time",
+        about: "Benchmark the solutions not benched yet, or the selected days",
+        operand: Operand::OptionalDays,
+        flags: &[
+            Flag {
+                name: "--all",
+                value: None,
+                about: "benchmark every day, even those already benched",
+            },
+            Flag {
+                name: "--store",
+                value: None,
+                about: "store the timings in the readme",
+            },
+        ],
+        extra_args: true,
+    },
+    Command {
+        name: "today",
+        about: "Scaffold, download and read the puzzle of the day (needs the `today` feature)",
+        operand: Operand::None,
+        flags: &[],
+        extra_args: false,
+    },
+    Command {
+        name: "wait",
+        about: "Count down to the next puzzle, then scaffold, download and read it (needs the `today` feature)",
+        operand: Operand::None,
+        flags: &[],
+        extra_args: false,
+    },
+    Command {
+        name: "init-year",
+        about: "Archive the solutions, data and timings of the current year and start a new one",
+        operand: Operand::Year,
+        flags: &[],
+        extra_args: false,
+    },
+    Command {
+        name: "completions",
+        about: "Print the completion script of a shell",
+        operand: Operand::Choice("<shell>", SHELLS),
+        flags: &[],
+        extra_args: false,
+    },
+];
+
+pub fn command(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|c| c.name == name)
+}
+
+/// The overview of every subcommand.
+pub fn usage() -> String {
+    let mut usage = String::from("Usage: cargo <command> [<args>]\n\nCommands:\n");
+    let width = COMMANDS.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for command in COMMANDS {
+        let _ = writeln!(usage, "  {:width$}  {}", command.name, command.about);
+    }
+    usage.push_str("\nFlags:\n");
+    for flag in GLOBAL_FLAGS {
+        let _ = writeln!(usage, "  {}  {}", flag.usage(), flag.about);
+    }
+    usage.push_str("\nRun `cargo <command> --help` for the flags of a command.\n");
+    usage
+}
+
+impl Command {
+    /// The usage line of the command, e.g. `cargo solve <day> [--release]...`.
+    pub fn synopsis(&self) -> String {
+        let mut synopsis = format!("cargo {}", self.name);
+        match self.operand {
+            Operand::None => {}
+            Operand::Day => synopsis.push_str(" <day>"),
+            Operand::Days => synopsis.push_str(" <days>"),
+            Operand::OptionalDays => synopsis.push_str(" [<days>]"),
+            Operand::Year => synopsis.push_str(" <year>"),
+            Operand::Choice(placeholder, _) => {
+                let _ = write!(synopsis, " {placeholder}");
+            }
+        }
+        for flag in self.flags {
+            let _ = write!(synopsis, " [{}]", flag.usage());
+        }
+        if self.extra_args {
+            synopsis.push_str(" [-- <cargo args>...]");
+        }
+        synopsis
+    }
+
+    pub fn usage(&self) -> String {
+        let mut usage = format!("{}\n\nUsage: {}\n", self.about, self.synopsis());
+        match self.operand {
+            Operand::Choice(placeholder, choices) => {
+                let _ = writeln!(usage, "\n{placeholder} is one of: {}", choices.join(", "));
+            }
+            Operand::Days | Operand::OptionalDays => usage.push_str(DAYS_USAGE),
+            Operand::None | Operand::Day | Operand::Year => {}
+        }
+        usage.push_str("\nFlags:\n");
+        let width = self.all_flags().map(|f| f.usage().len()).max().unwrap_or(0);
+        for flag in self.all_flags() {
+            let _ = writeln!(usage, "  {:width$}  {}", flag.usage(), flag.about);
+        }
+        usage
+    }
+
+    /// The flags of the command, followed by the [`GLOBAL_FLAGS`].
+    pub fn all_flags(&self) -> impl Iterator<Item = &'static Flag> {
+        self.flags.iter().chain(GLOBAL_FLAGS)
+    }
+}
+
+impl Flag {
+    fn usage(&self) -> String {
+        match self.value {
+            Some(value) => format!("{} {value}", self.name),
+            None => self.name.to_string(),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_every_command() {
+        let usage = usage();
+        for command in COMMANDS {
+            assert!(usage.contains(command.name));
+        }
+        assert_eq!(
+            command("solve").unwrap().synopsis(),
+            "cargo solve <days> [--release] [--dhat] [--submit <part>] [--example] \
+             [--example-part <part>] [--input <path>] [-- <cargo args>...]"
+        );
+        assert!(command("time").unwrap().usage().contains("  --store  "));
+        assert!(command("read").unwrap().usage().contains("--color <when>"));
+        assert!(command("nope").is_none());
+    }
+}
@@ -1,6 +1,8 @@
-use crate::template::{all_days, run_multi::run_multi};
+use crate::template::config::Config;
+use crate::template::{DaySet, run_multi::run_multi};
 use std::ffi::OsString;
 
-pub fn handle(is_release: bool, extra_args: &[OsString]) {
-    run_multi(&all_days().collect(), is_release, false, extra_args);
+pub fn handle(days: &DaySet, is_release: bool, extra_args: &[OsString]) {
+    let is_release = is_release || Config::get().defaults.release;
+    run_multi(&days.resolve(), is_release, false, extra_args);
 }
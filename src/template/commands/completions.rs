@@ -0,0 +1,241 @@
+use crate::template::DAY_SET_KEYWORDS;
+use crate::template::cli::{COMMANDS, Command, Operand, SHELLS};
+use crate::template::config::Config;
+use std::fmt::Write;
+use std::process;
+
+/// Shell snippet listing the days that have a solution, run when the shell
+/// asks for completions.
+fn list_days() -> String {
+    format!(
+        r"ls '{}' 2>/dev/null | sed -n 's/^\([0-9][0-9]\)\.rs$/\1/p'",
+        Config::get().paths.solutions.display()
+    )
+}
+
+pub fn handle(shell: &str) {
+    let script = match shell {
+        "bash" => bash(),
+        "zsh" => zsh(),
+        "fish" => fish(),
+        _ => {
+            eprintln!(
+                "Unknown shell: {shell}. Supported shells: {}.",
+                SHELLS.join(", ")
+            );
+            process::exit(1);
+        }
+    };
+    print!("{script}");
+}
+
+/// Words to complete as the operand of `command`: a shell snippet or a word list.
+fn operand_words(command: &Command) -> Option<String> {
+    match command.operand {
+        Operand::None | Operand::Year => None,
+        Operand::Day => Some(format!("$({})", list_days())),
+        Operand::Days | Operand::OptionalDays => {
+            Some(format!("$({}) {}", list_days(), DAY_SET_KEYWORDS.join(" ")))
+        }
+        Operand::Choice(_, choices) => Some(choices.join(" ")),
+    }
+}
+
+fn flag_names(command: &Command) -> String {
+    command
+        .all_flags()
+        .map(|f| f.name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn command_names() -> String {
+    COMMANDS
+        .iter()
+        .map(|c| c.name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bash() -> String {
+    let mut script = String::from(
+        "# bash completion of the advent of code cargo aliases\n\
+         # usage: source <(cargo completions bash)\n\
+         _aoc_cargo() {\n    \
+             local cur=${COMP_WORDS[COMP_CWORD]}\n    \
+             case \"$COMP_CWORD:${COMP_WORDS[1]}\" in\n",
+    );
+    let _ = writeln!(
+        script,
+        "        1:*)\n            \
+                 declare -F _cargo >/dev/null && _cargo \"$@\"\n            \
+                 COMPREPLY+=($(compgen -W \"{}\" -- \"$cur\"))\n            \
+                 return ;;",
+        command_names()
+    );
+    for command in COMMANDS {
+        let words = [operand_words(command), Some(flag_names(command))]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            script,
+            "        *:{})\n            \
+                     COMPREPLY=($(compgen -W \"{words}\" -- \"$cur\"))\n            \
+                     return ;;",
+            command.name
+        );
+    }
+    script.push_str(
+        "    esac\n    \
+             declare -F _cargo >/dev/null && _cargo \"$@\"\n\
+         }\n\
+         complete -o default -F _aoc_cargo cargo\n",
+    );
+    script
+}
+
+fn zsh() -> String {
+    let list_days = list_days();
+    let keywords = DAY_SET_KEYWORDS.join(" ");
+    let mut script = format!(
+        "#compdef cargo\n\
+         # zsh completion of the advent of code cargo aliases\n\
+         # usage: source <(cargo completions zsh)\n\
+         _aoc_days() {{\n    \
+             compadd -- ${{(f)\"$({list_days})\"}}\n\
+         }}\n\
+         _aoc_day_sets() {{\n    \
+             _aoc_days\n    \
+             compadd -S '' -- {keywords}\n\
+         }}\n\
+         _aoc_cargo() {{\n    \
+             local -a commands\n    \
+             commands=(\n",
+    );
+    for command in COMMANDS {
+        let _ = writeln!(
+            script,
+            "        '{}:{}'",
+            command.name,
+            command.about.replace('\'', "'\\''")
+        );
+    }
+    script.push_str(
+        "    )\n    \
+             if (( CURRENT == 2 )); then\n        \
+                 _describe -t aoc-commands 'advent of code command' commands\n        \
+                 (( $+functions[_cargo] )) && _cargo\n        \
+                 return\n    \
+             fi\n    \
+             case $words[2] in\n",
+    );
+    for command in COMMANDS {
+        let mut specs: Vec<String> = command
+            .all_flags()
+            .map(|f| match f.value {
+                Some(value) => format!("'{}[{}]:{}'", f.name, f.about, value),
+                None => format!("'{}[{}]'", f.name, f.about),
+            })
+            .collect();
+        match command.operand {
+            Operand::None => {}
+            Operand::Year => specs.push("'1:year:'".to_string()),
+            Operand::Day => specs.push("'1:day:_aoc_days'".to_string()),
+            Operand::Days => specs.push("'1:days:_aoc_day_sets'".to_string()),
+            Operand::OptionalDays => specs.push("'::days:_aoc_day_sets'".to_string()),
+            Operand::Choice(placeholder, choices) => specs.push(format!(
+                "'1:{}:({})'",
+                placeholder.trim_matches(['<', '>']),
+                choices.join(" ")
+            )),
+        }
+        let _ = writeln!(
+            script,
+            "        {}) _arguments -s {} ;;",
+            command.name,
+            specs.join(" ")
+        );
+    }
+    script.push_str(
+        "        *) (( $+functions[_cargo] )) && _cargo ;;\n    \
+             esac\n\
+         }\n\
+         compdef _aoc_cargo cargo\n",
+    );
+    script
+}
+
+fn fish() -> String {
+    let list_days = list_days();
+    let mut script = format!(
+        "# fish completion of the advent of code cargo aliases\n\
+         # usage: cargo completions fish | source\n\
+         function __aoc_days\n    {list_days}\nend\n"
+    );
+    for command in COMMANDS {
+        let _ = writeln!(
+            script,
+            "complete -c cargo -n __fish_use_subcommand -f -a {} -d '{}'",
+            command.name,
+            command.about.replace('\'', "\\'")
+        );
+        let condition = format!("'__fish_seen_subcommand_from {}'", command.name);
+        match command.operand {
+            Operand::None => {}
+            Operand::Year => {
+                let _ = writeln!(script, "complete -c cargo -n {condition} -f");
+            }
+            Operand::Day => {
+                let _ = writeln!(
+                    script,
+                    "complete -c cargo -n {condition} -f -a '(__aoc_days)'"
+                );
+            }
+            Operand::Days | Operand::OptionalDays => {
+                let _ = writeln!(
+                    script,
+                    "complete -c cargo -n {condition} -f -a '(__aoc_days) {}'",
+                    DAY_SET_KEYWORDS.join(" ")
+                );
+            }
+            Operand::Choice(_, choices) => {
+                let _ = writeln!(
+                    script,
+                    "complete -c cargo -n {condition} -f -a '{}'",
+                    choices.join(" ")
+                );
+            }
+        }
+        for flag in command.all_flags() {
+            let takes_value = if flag.value.is_some() { " -r" } else { "" };
+            let _ = writeln!(
+                script,
+                "complete -c cargo -n {condition} -l {}{takes_value} -d '{}'",
+                flag.name.trim_start_matches("--"),
+                flag.about.replace('\'', "\\'")
+            );
+        }
+    }
+    script
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_every_command() {
+        for script in [bash(), zsh(), fish()] {
+            for command in COMMANDS {
+                assert!(script.contains(command.name), "{}", command.name);
+            }
+            assert!(script.contains("submit"));
+            assert!(script.contains("color"));
+            assert!(script.contains("src/bin"));
+        }
+    }
+}
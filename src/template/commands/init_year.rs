@@ -0,0 +1,232 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use crate::template::config::{self, CONFIG_FILE_PATH, Config, Paths, Readme};
+use crate::template::timings::Timings;
+use crate::template::{Day, all_days, readme_benchmarks};
+
+const MANIFEST_PATH: &str = "Cargo.toml";
+
+/// Archives the solutions, data and timings of the configured year into its
+/// own folder, then starts `year` from scratch.
+pub fn handle(year: u16) {
+    let config = Config::get();
+
+    if !config.bin_prefix.is_empty() {
+        eprintln!("`AOC_YEAR` selects an archived year, unset it to start a new one.");
+        process::exit(1);
+    }
+    let Some(current_year) = config.year else {
+        eprintln!("No `year` set in {CONFIG_FILE_PATH}, there is nothing to archive.");
+        process::exit(1);
+    };
+    if year == current_year {
+        eprintln!("{year} is already the configured year.");
+        process::exit(1);
+    }
+    let archive_dir = config.archive_dir(current_year);
+    if archive_dir.exists() {
+        eprintln!("\"{}\" already exists.", archive_dir.display());
+        process::exit(1);
+    }
+
+    let archived = archived_config(config, current_year);
+    if let Err(e) = archive(config, &archived) {
+        eprintln!("Failed to archive {current_year}: {e}");
+        process::exit(1);
+    }
+    println!("Archived {current_year} to \"{}\"", archive_dir.display());
+
+    if let Err(e) = start_year(year) {
+        eprintln!("Failed to set the year in {CONFIG_FILE_PATH}: {e}");
+        process::exit(1);
+    }
+    if readme_benchmarks::update_with(config, Timings::default()).is_err() {
+        eprintln!("Failed to reset the benchmarks of the readme.");
+    }
+
+    println!("---");
+    println!(
+        "🎄 Ready for {year}! Run `AOC_YEAR={current_year} cargo all` to run {current_year} again."
+    );
+}
+
+/// The configuration of an archived year, everything living in its folder.
+fn archived_config(config: &Config, year: u16) -> Config {
+    let dir = config.archive_dir(year);
+    let data = dir.join("data");
+    Config {
+        year: Some(year),
+        last_day: config.last_day,
+        bin_prefix: format!("{year}-"),
+        paths: Paths {
+            inputs: data.join("inputs"),
+            examples: data.join("examples"),
+            puzzles: data.join("puzzles"),
+            timings: data.join("timings.json"),
+            data,
+            solutions: dir.join("bin"),
+            archive: config.paths.archive.clone(),
+        },
+        defaults: config.defaults.clone(),
+        readme: Readme {
+            file: dir.join("README.md"),
+            marker: config.readme.marker.clone(),
+        },
+    }
+}
+
+/// Copies everything into the archive folder and registers the archived bins
+/// first, and only then removes the originals: when any of it fails, the
+/// archive folder is removed and the current year is left untouched.
+fn archive(config: &Config, archived: &Config) -> io::Result<()> {
+    let days: Vec<Day> = all_days()
+        .filter(|day| config.solution_path(*day).exists())
+        .collect();
+    let moves = archive_moves(config, archived, &days)?;
+
+    if let Err(e) = copy_to_archive(archived, &moves).and_then(|()| register_bins(archived, &days))
+    {
+        let archive_dir = config.archive_dir(archived.year.unwrap_or_default());
+        if fs::remove_dir_all(&archive_dir).is_err() {
+            eprintln!("Failed to remove \"{}\".", archive_dir.display());
+        }
+        return Err(e);
+    }
+
+    for (from, _) in &moves {
+        if let Err(e) = fs::remove_file(from) {
+            eprintln!("Failed to remove \"{}\": {e}", from.display());
+        }
+    }
+    Ok(())
+}
+
+/// Every file to move, as `(from, to)`: the data files but for the `.keep` of
+/// their folders, the timings and the solutions.
+fn archive_moves(
+    config: &Config,
+    archived: &Config,
+    days: &[Day],
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut moves = Vec::new();
+    for (from, to) in [
+        (&config.paths.inputs, &archived.paths.inputs),
+        (&config.paths.examples, &archived.paths.examples),
+        (&config.paths.puzzles, &archived.paths.puzzles),
+    ] {
+        if !from.exists() {
+            continue;
+        }
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            if entry.file_name() != ".keep" {
+                moves.push((entry.path(), to.join(entry.file_name())));
+            }
+        }
+    }
+    if config.paths.timings.exists() {
+        moves.push((config.paths.timings.clone(), archived.paths.timings.clone()));
+    }
+    for day in days {
+        moves.push((config.solution_path(*day), archived.solution_path(*day)));
+    }
+    Ok(moves)
+}
+
+/// Copies the files to archive, then writes the readme and settings of the
+/// archived year.
+fn copy_to_archive(archived: &Config, moves: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    for dir in [
+        &archived.paths.inputs,
+        &archived.paths.examples,
+        &archived.paths.puzzles,
+        &archived.paths.solutions,
+    ] {
+        fs::create_dir_all(dir)?;
+    }
+    for (from, to) in moves {
+        fs::copy(from, to)?;
+    }
+
+    let year = archived.year.unwrap_or_default();
+    let marker = &archived.readme.marker;
+    fs::write(
+        &archived.readme.file,
+        format!("# Advent of Code {year}\n\n{marker}\n{marker}\n"),
+    )?;
+    if readme_benchmarks::update_with(archived, Timings::read_from(&archived.paths.timings))
+        .is_err()
+    {
+        eprintln!("Failed to write the benchmarks of the archived readme.");
+    }
+
+    fs::write(
+        archived.archived_config_path(year),
+        format!(
+            "# Settings of the archived {year} solutions, used when `AOC_YEAR={year}`.\n\n{}",
+            archived.to_toml()
+        ),
+    )
+}
+
+/// Declares the archived solutions in the manifest, as `<year>-<day>` binaries,
+/// so they keep building next to the new ones.
+fn register_bins(archived: &Config, days: &[Day]) -> io::Result<()> {
+    let manifest = fs::read_to_string(MANIFEST_PATH)?;
+    let entries = bin_entries(&manifest, archived, days);
+    if entries.is_empty() {
+        return Ok(());
+    }
+    fs::write(MANIFEST_PATH, manifest + &entries)
+}
+
+/// The `[[bin]]` entries of the archived solutions, but for those the manifest
+/// already declares.
+fn bin_entries(manifest: &str, archived: &Config, days: &[Day]) -> String {
+    let mut entries = String::new();
+    for day in days {
+        let name = archived.bin_name(*day);
+        if manifest.contains(&format!("name = \"{name}\"")) {
+            continue;
+        }
+        let path = archived.solution_path(*day).display().to_string();
+        entries.push_str(&format!(
+            "\n[[bin]]\nname = \"{name}\"\npath = \"{}\"\ntest = false\n",
+            path.replace('\\', "/")
+        ));
+    }
+    entries
+}
+
+fn start_year(year: u16) -> io::Result<()> {
+    let content = fs::read_to_string(CONFIG_FILE_PATH).unwrap_or_default();
+    fs::write(CONFIG_FILE_PATH, config::set_year(&content, year))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_registered_bins() {
+        let archived = Config {
+            bin_prefix: "2024-".to_string(),
+            ..Config::default()
+        };
+        let days = [Day::new(1).unwrap(), Day::new(2).unwrap()];
+        let manifest = "[[bin]]\nname = \"2024-01\"\npath = \"years/2024/bin/01.rs\"\n";
+
+        let entries = bin_entries(manifest, &archived, &days);
+        assert!(!entries.contains("2024-01"));
+        assert!(entries.contains("name = \"2024-02\""));
+        assert_eq!(
+            bin_entries(&(manifest.to_string() + &entries), &archived, &days),
+            ""
+        );
+    }
+}
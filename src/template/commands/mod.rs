@@ -1,6 +1,10 @@
 pub mod all;
+pub mod completions;
 pub mod download;
+pub mod init_year;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
 pub mod time;
+#[cfg(feature = "today")]
+pub mod wait;
@@ -5,6 +5,7 @@ use std::{
 };
 
 use crate::template::Day;
+use crate::template::config::Config;
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
@@ -28,9 +29,10 @@ fn create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+    let config = Config::get();
+    let input_path = config.input_path(day).display().to_string();
+    let example_path = config.example_path(day).display().to_string();
+    let module_path = config.solution_path(day).display().to_string();
 
     let mut file = match safe_create_file(&module_path, overwrite) {
         Ok(file) => file,
@@ -2,15 +2,23 @@ use std::ffi::OsString;
 use std::process::{Command, Stdio};
 
 use crate::template::Day;
+use crate::template::config::Config;
+use crate::template::input::InputSource;
+use crate::template::style;
 
 pub fn handle(
     day: Day,
     release: bool,
     dhat: bool,
     submit_part: Option<u8>,
+    input: &InputSource,
     extra_args: &[OsString],
 ) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--bin".to_string(),
+        Config::get().bin_name(day),
+    ];
 
     if dhat {
         cmd_args.extend([
@@ -19,7 +27,7 @@ pub fn handle(
             "--features".to_string(),
             "dhat-heap".to_string(),
         ]);
-    } else if release {
+    } else if release || Config::get().defaults.release {
         cmd_args.push("--release".to_string());
     }
     for arg in extra_args.iter().filter_map(|a| a.to_str()) {
@@ -27,11 +35,13 @@ pub fn handle(
     }
 
     cmd_args.push("--".to_string());
+    cmd_args.push(style::color_arg().to_string());
 
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
     }
+    cmd_args.extend(input.to_args());
 
     eprintln!("about to run cargo {}", &cmd_args.join(" "));
 
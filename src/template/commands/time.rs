@@ -1,25 +1,21 @@
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{Day, all_days, readme_benchmarks};
-use std::collections::HashSet;
+use crate::template::{DaySet, readme_benchmarks};
 use std::ffi::OsString;
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool, extra_args: &[OsString]) {
+pub fn handle(days: Option<DaySet>, run_all: bool, store: bool, extra_args: &[OsString]) {
     let stored_timings = Timings::read_from_file();
 
-    let days_to_run = day.map_or_else(
-        || {
+    // when neither days nor the `--all` flag are set, filter out days that are fully benched.
+    let days_to_run = days
+        .unwrap_or_else(|| {
             if run_all {
-                all_days().collect()
+                DaySet::all()
             } else {
-                // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
-                    .collect()
+                DaySet::unbenched()
             }
-        },
-        |day| HashSet::from([day]),
-    );
+        })
+        .resolve();
 
     let timings = run_multi(&days_to_run, true, true, extra_args).unwrap();
 
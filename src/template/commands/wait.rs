@@ -0,0 +1,123 @@
+use std::io::{Write, stdout};
+use std::process;
+use std::thread::sleep;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Utc};
+
+use crate::template::aoc_cli::AocCommandError;
+use crate::template::commands::{read, scaffold};
+use crate::template::config::Config;
+use crate::template::style::is_interactive;
+use crate::template::{Day, aoc_cli};
+
+/// How many times the input is requested before giving up, the puzzle taking
+/// a few seconds to become available after its unlock.
+const DOWNLOAD_ATTEMPTS: u32 = 10;
+
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+pub fn handle() {
+    if aoc_cli::check().is_err() {
+        eprintln!(
+            "command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it."
+        );
+        process::exit(1);
+    }
+
+    let config = Config::get();
+    let Some((day, unlock)) = Day::next_unlock(Utc::now(), config.last_day) else {
+        eprintln!("Could not compute the next unlock.");
+        process::exit(1);
+    };
+    if let Some(year) = config.year()
+        && i32::from(year) < unlock.year()
+    {
+        eprintln!(
+            "No more puzzles in {year}, day {} was the last one. \
+             Run `cargo init-year {}` to wait for the next ones.",
+            config.last_day,
+            unlock.year()
+        );
+        process::exit(1);
+    }
+    if let Some(year) = config.year()
+        && i32::from(year) != unlock.year()
+    {
+        eprintln!(
+            "The next puzzle unlocks in {}, but the configured year is {year}. \
+             Run `cargo init-year {}` first.",
+            unlock.year(),
+            unlock.year()
+        );
+        process::exit(1);
+    }
+
+    countdown(day, unlock);
+
+    if config.solution_path(day).exists() {
+        println!("Day {day} is already scaffolded.");
+    } else {
+        scaffold::handle(day, false);
+    }
+    download(day);
+    read::handle(day);
+}
+
+/// Prints the time left until `unlock` every second.
+fn countdown(day: Day, unlock: DateTime<Utc>) {
+    let mut stdout = stdout();
+    if !is_interactive() {
+        if let Ok(remaining) = (unlock - Utc::now()).to_std() {
+            println!("⏳ Day {day} unlocks in {}", format_remaining(remaining));
+            sleep(remaining);
+        }
+        println!("🔓 Day {day} is unlocked!");
+        return;
+    }
+    while let Ok(remaining) = (unlock - Utc::now()).to_std() {
+        print!("\r⏳ Day {day} unlocks in {}", format_remaining(remaining));
+        let _ = stdout.flush();
+        // wake up on the next whole second of the countdown.
+        let tick = match remaining.subsec_nanos() {
+            0 => Duration::from_secs(1),
+            nanos => Duration::from_nanos(nanos.into()),
+        };
+        sleep(tick);
+    }
+    println!("\r🔓 Day {day} is unlocked!           ");
+}
+
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match seconds / 86400 {
+        0 => time,
+        days => format!("{days}d {time}"),
+    }
+}
+
+/// Downloads the input, retrying with an exponential backoff while aoc-cli
+/// fails to fetch it.
+fn download(day: Day) {
+    let mut delay = Duration::from_secs(1);
+    for attempt in 1..=DOWNLOAD_ATTEMPTS {
+        match aoc_cli::download(day) {
+            Ok(_) => return,
+            Err(AocCommandError::BadExitStatus(_)) if attempt < DOWNLOAD_ATTEMPTS => {
+                eprintln!("Input not available yet, retrying in {delay:?}...");
+                sleep(delay);
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+            Err(e) => {
+                eprintln!("failed to call aoc-cli: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,479 @@
+/// Project settings, read once from `aoc.toml` at the root of the repository.
+///
+/// Only the small subset of TOML used by that file is supported: `[section]`
+/// headers and `key = value` lines, with string, integer or boolean values.
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::{env, fs};
+
+use crate::template::Day;
+
+pub const CONFIG_FILE_PATH: &str = "aoc.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The puzzle year. The `AOC_YEAR` environment variable takes precedence.
+    pub year: Option<u16>,
+    /// The last puzzle of the year, e.g. 12 since 2025.
+    pub last_day: Day,
+    /// Prefix of the solution binaries, e.g. `2024-` for an archived year.
+    pub bin_prefix: String,
+    pub paths: Paths,
+    pub defaults: Defaults,
+    pub readme: Readme,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    pub data: PathBuf,
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    pub solutions: PathBuf,
+    pub timings: PathBuf,
+    /// Where `init-year` archives previous years, one folder per year.
+    pub archive: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Defaults {
+    /// Build solutions with optimizations even without `--release`.
+    pub release: bool,
+    /// How long a part is run when benching.
+    pub bench_duration: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Readme {
+    pub file: PathBuf,
+    /// The comment surrounding the benchmarks table.
+    pub marker: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: None,
+            last_day: Day::new(25).unwrap(),
+            bin_prefix: String::new(),
+            paths: Paths {
+                data: "data".into(),
+                inputs: "data/inputs".into(),
+                examples: "data/examples".into(),
+                puzzles: "data/puzzles".into(),
+                solutions: "src/bin".into(),
+                timings: "data/timings.json".into(),
+                archive: "years".into(),
+            },
+            defaults: Defaults {
+                release: false,
+                bench_duration: Duration::from_secs(1),
+            },
+            readme: Readme {
+                file: "README.md".into(),
+                marker: "<!--- benchmarking table --->".into(),
+            },
+        }
+    }
+}
+
+impl Config {
+    /// The project configuration, defaults if there is no `aoc.toml`. When
+    /// `AOC_YEAR` names an archived year, the configuration of that year.
+    ///
+    /// # Panics
+    /// Panics if a configuration file can't be parsed.
+    pub fn get() -> &'static Self {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            let current = Self::read(Path::new(CONFIG_FILE_PATH));
+            match env_year() {
+                Some(year) if current.year != Some(year) => {
+                    let archived = current.archived_config_path(year);
+                    if archived.exists() {
+                        Self::read(&archived)
+                    } else {
+                        current
+                    }
+                }
+                _ => current,
+            }
+        })
+    }
+
+    fn read(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                Self::parse(&content).unwrap_or_else(|e| panic!("invalid {}: {e}", path.display()))
+            }
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        for Entry { key, line, value } in parse_entries(content)? {
+            let error = |message: &str| ConfigError {
+                line,
+                message: format!("`{key}` {message}"),
+            };
+            match key.as_str() {
+                "year" => config.year = Some(value.integer(&error)?),
+                "last_day" => {
+                    config.last_day = Day::new(value.integer(&error)?)
+                        .ok_or_else(|| error("must be between 1 and 25"))?;
+                }
+                "bin_prefix" => config.bin_prefix = value.string(&error)?,
+                "paths.data" => config.paths.data = value.string(&error)?.into(),
+                "paths.inputs" => config.paths.inputs = value.string(&error)?.into(),
+                "paths.examples" => config.paths.examples = value.string(&error)?.into(),
+                "paths.puzzles" => config.paths.puzzles = value.string(&error)?.into(),
+                "paths.solutions" => config.paths.solutions = value.string(&error)?.into(),
+                "paths.timings" => config.paths.timings = value.string(&error)?.into(),
+                "paths.archive" => config.paths.archive = value.string(&error)?.into(),
+                "defaults.release" => config.defaults.release = value.boolean(&error)?,
+                "defaults.bench_duration_ms" => {
+                    config.defaults.bench_duration = Duration::from_millis(value.integer(&error)?);
+                }
+                "readme.file" => config.readme.file = value.string(&error)?.into(),
+                "readme.marker" => config.readme.marker = value.string(&error)?,
+                _ => return Err(error("is not a known setting")),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Writes the configuration back, in the format of `aoc.toml`.
+    pub fn to_toml(&self) -> String {
+        let string = |s: &str| {
+            let escaped = s
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t");
+            format!("\"{escaped}\"")
+        };
+        let path = |p: &Path| string(&p.display().to_string());
+        let mut toml = String::new();
+        if let Some(year) = self.year {
+            toml.push_str(&format!("year = {year}\n"));
+        }
+        toml.push_str(&format!("last_day = {}\n", self.last_day.into_inner()));
+        toml.push_str(&format!("bin_prefix = {}\n", string(&self.bin_prefix)));
+        toml.push_str("\n[paths]\n");
+        for (key, value) in [
+            ("data", &self.paths.data),
+            ("inputs", &self.paths.inputs),
+            ("examples", &self.paths.examples),
+            ("puzzles", &self.paths.puzzles),
+            ("solutions", &self.paths.solutions),
+            ("timings", &self.paths.timings),
+            ("archive", &self.paths.archive),
+        ] {
+            toml.push_str(&format!("{key} = {}\n", path(value)));
+        }
+        toml.push_str(&format!(
+            "\n[defaults]\nrelease = {}\nbench_duration_ms = {}\n",
+            self.defaults.release,
+            self.defaults.bench_duration.as_millis()
+        ));
+        toml.push_str(&format!(
+            "\n[readme]\nfile = {}\nmarker = {}\n",
+            path(&self.readme.file),
+            string(&self.readme.marker)
+        ));
+        toml
+    }
+
+    /// The year to solve: `AOC_YEAR` if set, else the configured one.
+    pub fn year(&self) -> Option<u16> {
+        env_year().or(self.year)
+    }
+
+    /// The folder of an archived year.
+    pub fn archive_dir(&self, year: u16) -> PathBuf {
+        self.paths.archive.join(year.to_string())
+    }
+
+    pub fn archived_config_path(&self, year: u16) -> PathBuf {
+        self.archive_dir(year).join(CONFIG_FILE_PATH)
+    }
+
+    /// The name of the solution binary of a day, e.g. `01` or `2024-01`.
+    pub fn bin_name(&self, day: Day) -> String {
+        format!("{}{day}", self.bin_prefix)
+    }
+
+    /// The directory of a data folder: `inputs`, `examples`, `puzzles`, or
+    /// any other folder of the data directory.
+    pub fn data_dir(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.paths.inputs.clone(),
+            "examples" => self.paths.examples.clone(),
+            "puzzles" => self.paths.puzzles.clone(),
+            other => self.paths.data.join(other),
+        }
+    }
+
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.paths.inputs.join(format!("{day}.txt"))
+    }
+
+    pub fn example_path(&self, day: Day) -> PathBuf {
+        self.paths.examples.join(format!("{day}.txt"))
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.paths.puzzles.join(format!("{day}.md"))
+    }
+
+    pub fn solution_path(&self, day: Day) -> PathBuf {
+        self.paths.solutions.join(format!("{day}.rs"))
+    }
+}
+
+/// Sets the top-level `year` of the content of an `aoc.toml`, keeping
+/// everything else as is.
+pub fn set_year(content: &str, year: u16) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let top_level = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    let year_line = lines[..top_level].iter().position(|l| {
+        strip_comment(l)
+            .split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "year")
+    });
+    match year_line {
+        Some(i) => lines[i] = format!("year = {year}"),
+        None => lines.insert(0, format!("year = {year}")),
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn env_year() -> Option<u16> {
+    env::var("AOC_YEAR").ok().and_then(|y| y.parse().ok())
+}
+
+/* -------------------------------------------------------------------------- */
+
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+impl Value {
+    fn parse(raw: &str) -> Option<Self> {
+        if let Some(quoted) = raw.strip_prefix('"') {
+            let mut string = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => return chars.as_str().is_empty().then_some(Self::String(string)),
+                    '\\' => string.push(match chars.next()? {
+                        'n' => '\n',
+                        't' => '\t',
+                        c @ ('"' | '\\') => c,
+                        _ => return None,
+                    }),
+                    c => string.push(c),
+                }
+            }
+            return None;
+        }
+        match raw {
+            "true" => Some(Self::Boolean(true)),
+            "false" => Some(Self::Boolean(false)),
+            _ => raw.replace('_', "").parse().ok().map(Self::Integer),
+        }
+    }
+
+    fn string(self, error: &impl Fn(&str) -> ConfigError) -> Result<String, ConfigError> {
+        match self {
+            Self::String(s) => Ok(s),
+            _ => Err(error("must be a string")),
+        }
+    }
+
+    fn integer<T: TryFrom<i64>>(
+        self,
+        error: &impl Fn(&str) -> ConfigError,
+    ) -> Result<T, ConfigError> {
+        match self {
+            Self::Integer(i) => T::try_from(i).map_err(|_| error("is out of range")),
+            _ => Err(error("must be an integer")),
+        }
+    }
+
+    fn boolean(self, error: &impl Fn(&str) -> ConfigError) -> Result<bool, ConfigError> {
+        match self {
+            Self::Boolean(b) => Ok(b),
+            _ => Err(error("must be `true` or `false`")),
+        }
+    }
+}
+
+/// A `key = value` line, the key being prefixed by its section, e.g. `paths.inputs`.
+struct Entry {
+    key: String,
+    line: usize,
+    value: Value,
+}
+
+fn parse_entries(content: &str) -> Result<Vec<Entry>, ConfigError> {
+    let mut entries = Vec::new();
+    let mut seen = HashMap::new();
+    let mut section = String::new();
+    for (i, line) in content.lines().enumerate() {
+        let line_number = i + 1;
+        let error = |message: &str| ConfigError {
+            line: line_number,
+            message: message.into(),
+        };
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            section = header
+                .strip_suffix(']')
+                .ok_or_else(|| error("unclosed section header"))?
+                .trim()
+                .to_string();
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected `key = value`"))?;
+        let key = match section.as_str() {
+            "" => key.trim().to_string(),
+            section => format!("{section}.{}", key.trim()),
+        };
+        let value = Value::parse(value.trim()).ok_or_else(|| error("invalid value"))?;
+        if let Some(first) = seen.insert(key.clone(), line_number) {
+            return Err(error(&format!("`{key}` already set on line {first}")));
+        }
+        entries.push(Entry {
+            key,
+            line: line_number,
+            value,
+        });
+    }
+    Ok(entries)
+}
+
+/// Removes a trailing `# comment`, unless the `#` is inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ConfigError {}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Config, set_year};
+    use crate::template::Day;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_settings() {
+        let config = Config::parse(
+            r#"
+            year = 2024 # a comment
+            [paths]
+            inputs = "inputs/#2024"
+
+            [defaults]
+            release = true
+            bench_duration_ms = 250
+
+            [readme]
+            marker = "<!-- \"bench\" -->"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.year, Some(2024));
+        assert_eq!(config.paths.inputs.to_str(), Some("inputs/#2024"));
+        assert_eq!(config.paths.examples.to_str(), Some("data/examples"));
+        assert!(config.defaults.release);
+        assert_eq!(config.defaults.bench_duration, Duration::from_millis(250));
+        assert_eq!(config.readme.marker, r#"<!-- "bench" -->"#);
+    }
+
+    #[test]
+    fn parses_the_repository_config() {
+        let config = Config::parse(include_str!("../../aoc.toml")).unwrap();
+        assert_eq!(config.paths, Config::default().paths);
+        assert_eq!(config.last_day.into_inner(), 12);
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut config = Config::default();
+        config.year = Some(2023);
+        config.bin_prefix = "2023-".into();
+        config.last_day = Day::new(12).unwrap();
+        config.paths.solutions = "years/2023/bin".into();
+        config.readme.marker = r#"<!-- "bench" -->"#.into();
+        assert_eq!(Config::parse(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn sets_the_year() {
+        let content = "# settings\nyear = 2024 # comment\n\n[readme]\nyear = 1\n";
+        assert_eq!(
+            set_year(content, 2025),
+            "# settings\nyear = 2025\n\n[readme]\nyear = 1\n"
+        );
+        assert_eq!(set_year("[paths]\n", 2025), "year = 2025\n[paths]\n");
+    }
+
+    #[test]
+    fn reports_errors_with_lines() {
+        let error = Config::parse("year = 2024\n[defaults]\nrelease = 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: `defaults.release` must be `true` or `false`"
+        );
+
+        let error = Config::parse("\ncolour = \"red\"").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: `colour` is not a known setting");
+
+        assert_eq!(Config::parse("year = 20\nyear = 21").unwrap_err().line, 2);
+        assert_eq!(Config::parse("[paths\n").unwrap_err().line, 1);
+        assert_eq!(Config::parse("year = \"2024").unwrap_err().line, 1);
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 #[cfg(feature = "today")]
-use chrono::{Datelike, FixedOffset, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, TimeZone, Utc};
 
 #[cfg(feature = "today")]
 const SERVER_UTC_OFFSET: i32 = -5;
@@ -39,16 +39,37 @@ impl Day {
 
 #[cfg(feature = "today")]
 impl Day {
-    /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
-    pub fn today() -> Option<Self> {
+    /// Returns the current day if it's between the 1st of december and
+    /// `last_day`, `None` otherwise.
+    pub fn today(last_day: Self) -> Option<Self> {
+        Self::unlocked_on(Utc::now(), last_day)
+    }
+
+    fn unlocked_on(now: DateTime<Utc>, last_day: Self) -> Option<Self> {
         let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
-        let today = Utc::now().with_timezone(&offset);
-        if today.month() == 12 && today.day() <= 25 {
-            Self::new(u8::try_from(today.day()).ok()?)
+        let today = now.with_timezone(&offset);
+        if today.month() == 12 {
+            Self::new(u8::try_from(today.day()).ok()?).filter(|day| *day <= last_day)
         } else {
             None
         }
     }
+
+    /// Returns the next day to unlock after `now` and when it unlocks, at
+    /// midnight on the server. After `last_day`, that's the 1st of next year.
+    pub fn next_unlock(now: DateTime<Utc>, last_day: Self) -> Option<(Self, DateTime<Utc>)> {
+        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+        let today = now.with_timezone(&offset);
+        let (year, day) = match Self::unlocked_on(now, last_day) {
+            Some(day) if day < last_day => (today.year(), day.0 + 1),
+            _ if today.month() == 12 => (today.year() + 1, 1),
+            _ => (today.year(), 1),
+        };
+        let unlock = offset
+            .with_ymd_and_hms(year, 12, u32::from(day), 0, 0, 0)
+            .single()?;
+        Some((Self::new(day)?, unlock.with_timezone(&Utc)))
+    }
 }
 
 impl Display for Day {
@@ -176,6 +197,59 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    #[cfg(feature = "today")]
+    fn unlocked_on() {
+        use chrono::{DateTime, Utc};
+
+        let unlocked = |now: &str, last_day: u8| {
+            let now = DateTime::parse_from_rfc3339(now)
+                .unwrap()
+                .with_timezone(&Utc);
+            Day::unlocked_on(now, Day(last_day)).map(|day| day.0)
+        };
+        assert_eq!(unlocked("2025-12-12T05:00:00Z", 12), Some(12));
+        assert_eq!(unlocked("2025-12-13T05:00:00Z", 12), None);
+        assert_eq!(unlocked("2024-12-13T05:00:00Z", 25), Some(13));
+        assert_eq!(unlocked("2024-12-26T05:00:00Z", 25), None);
+        assert_eq!(unlocked("2024-11-30T05:00:00Z", 25), None);
+    }
+
+    #[test]
+    #[cfg(feature = "today")]
+    fn next_unlock() {
+        use chrono::{DateTime, Utc};
+
+        let unlock = |now: &str| {
+            let now = DateTime::parse_from_rfc3339(now)
+                .unwrap()
+                .with_timezone(&Utc);
+            Day::next_unlock(now, Day(12)).map(|(day, at)| (day.0, at.to_rfc3339()))
+        };
+        let at = |day: u8, time: &str| Some((day, time.to_string()));
+
+        assert_eq!(
+            unlock("2025-06-01T12:00:00Z"),
+            at(1, "2025-12-01T05:00:00+00:00")
+        );
+        assert_eq!(
+            unlock("2025-12-01T04:59:59Z"),
+            at(1, "2025-12-01T05:00:00+00:00")
+        );
+        assert_eq!(
+            unlock("2025-12-01T05:00:00Z"),
+            at(2, "2025-12-02T05:00:00+00:00")
+        );
+        assert_eq!(
+            unlock("2025-12-12T05:00:00Z"),
+            at(1, "2026-12-01T05:00:00+00:00")
+        );
+        assert_eq!(
+            unlock("2025-12-26T04:00:00Z"),
+            at(1, "2026-12-01T05:00:00+00:00")
+        );
+    }
 }
 
 /* -------------------------------------------------------------------------- */
@@ -0,0 +1,219 @@
+/// Selection of days on the command line, e.g. `1-5,8,10-` or `unbenched`.
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::template::config::Config;
+use crate::template::timings::Timings;
+use crate::template::{Day, all_days};
+
+/// The keywords accepted by [`DaySet`], for help and completions.
+pub const DAY_SET_KEYWORDS: &[&str] = &["all", "unsolved", "unbenched", "slowest:"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Selector {
+    /// An inclusive range of days, a single day being a range of one.
+    Range(Day, Day),
+    All,
+    /// Scaffolded days without any stored timing.
+    Unsolved,
+    /// Scaffolded days with a part missing from the stored timings.
+    Unbenched,
+    /// The days with the largest stored total time.
+    Slowest(usize),
+}
+
+/// A comma separated list of days, ranges (`3-7`, `10-`, `-4`) and keywords
+/// (`all`, `unsolved`, `unbenched`, `slowest:<count>`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySet {
+    selectors: Vec<Selector>,
+}
+
+impl DaySet {
+    pub fn all() -> Self {
+        Self {
+            selectors: vec![Selector::All],
+        }
+    }
+
+    pub fn unbenched() -> Self {
+        Self {
+            selectors: vec![Selector::Unbenched],
+        }
+    }
+
+    /// The day, if the set is made of a single day.
+    pub fn single_day(&self) -> Option<Day> {
+        match self.selectors.as_slice() {
+            [Selector::Range(first, last)] if first == last => Some(*first),
+            _ => None,
+        }
+    }
+
+    /// The selected days, keywords being resolved from the stored timings and
+    /// the scaffolded solutions.
+    pub fn resolve(&self) -> HashSet<Day> {
+        let config = Config::get();
+        self.resolve_with(&Timings::read_from_file(), |day| {
+            config.solution_path(day).exists()
+        })
+    }
+
+    fn resolve_with(&self, timings: &Timings, is_scaffolded: impl Fn(Day) -> bool) -> HashSet<Day> {
+        let timing = |day: Day| timings.data.iter().find(|t| t.day == day);
+        self.selectors
+            .iter()
+            .flat_map(|selector| -> Vec<Day> {
+                match *selector {
+                    Selector::Range(first, last) => all_days()
+                        .filter(|day| (first..=last).contains(day))
+                        .collect(),
+                    Selector::All => all_days().collect(),
+                    Selector::Unsolved => all_days()
+                        .filter(|day| is_scaffolded(*day))
+                        .filter(|day| {
+                            timing(*day).is_none_or(|t| t.part_1.is_none() && t.part_2.is_none())
+                        })
+                        .collect(),
+                    Selector::Unbenched => all_days()
+                        .filter(|day| is_scaffolded(*day) && !timings.is_day_complete(*day))
+                        .collect(),
+                    Selector::Slowest(count) => {
+                        let mut benched = timings.data.clone();
+                        benched.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos));
+                        benched.iter().take(count).map(|t| t.day).collect()
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl FromStr for DaySet {
+    type Err = DaySetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let day = |d: &str, default: u8| -> Result<Day, DaySetError> {
+            let d = d.trim();
+            if d.is_empty() {
+                return Ok(Day::new(default).unwrap());
+            }
+            d.parse().map_err(|_| DaySetError(d.to_string()))
+        };
+
+        let selectors = s
+            .split(',')
+            .map(|item| {
+                let item = item.trim();
+                let selector = match item {
+                    "all" => Selector::All,
+                    "unsolved" => Selector::Unsolved,
+                    "unbenched" => Selector::Unbenched,
+                    _ => match (item.strip_prefix("slowest:"), item.split_once('-')) {
+                        (Some(count), _) => Selector::Slowest(
+                            count.parse().map_err(|_| DaySetError(item.to_string()))?,
+                        ),
+                        (None, Some((first, last))) => {
+                            Selector::Range(day(first, 1)?, day(last, 25)?)
+                        }
+                        (None, None) if !item.is_empty() => {
+                            let day = day(item, 1)?;
+                            Selector::Range(day, day)
+                        }
+                        (None, None) => return Err(DaySetError(item.to_string())),
+                    },
+                };
+                match selector {
+                    Selector::Range(first, last) if first > last => {
+                        Err(DaySetError(item.to_string()))
+                    }
+                    selector => Ok(selector),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { selectors })
+    }
+}
+
+/// An error returned for an item of a [`DaySet`] that can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySetError(String);
+
+impl Error for DaySetError {}
+
+impl Display for DaySetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid day selection `{}`, expecting days (1-25), ranges like `3-7` or `10-`, \
+             or one of `all`, `unsolved`, `unbenched`, `slowest:<count>`",
+            self.0
+        )
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::DaySet;
+    use crate::day;
+    use crate::template::Day;
+    use crate::template::timings::{Timing, Timings};
+    use std::collections::HashSet;
+
+    fn days(days: &[u8]) -> HashSet<Day> {
+        days.iter().map(|d| Day::new(*d).unwrap()).collect()
+    }
+
+    fn timings() -> Timings {
+        let timing = |day, part_2: Option<&str>, total_nanos| Timing {
+            day,
+            part_1: Some("1ms".into()),
+            part_2: part_2.map(Into::into),
+            total_nanos,
+            status: Default::default(),
+        };
+        Timings {
+            data: vec![
+                timing(day!(1), Some("1ms"), 2e6),
+                timing(day!(2), None, 1e6),
+                timing(day!(3), Some("9ms"), 9e6),
+            ],
+        }
+    }
+
+    fn resolve(s: &str) -> HashSet<Day> {
+        s.parse::<DaySet>()
+            .unwrap()
+            .resolve_with(&timings(), |day| day <= 4)
+    }
+
+    #[test]
+    fn parses_days_and_ranges() {
+        assert_eq!(resolve("1-3,8, 10"), days(&[1, 2, 3, 8, 10]));
+        assert_eq!(resolve("23-"), days(&[23, 24, 25]));
+        assert_eq!(resolve("-2"), days(&[1, 2]));
+        assert_eq!(resolve("all").len(), 25);
+        assert_eq!("7".parse::<DaySet>().unwrap().single_day(), Some(day!(7)));
+        assert_eq!("7-8".parse::<DaySet>().unwrap().single_day(), None);
+    }
+
+    #[test]
+    fn resolves_keywords() {
+        assert_eq!(resolve("unsolved"), days(&[4]));
+        assert_eq!(resolve("unbenched"), days(&[2, 4]));
+        assert_eq!(resolve("slowest:2"), days(&[3, 1]));
+        assert_eq!(resolve("slowest:2,25"), days(&[1, 3, 25]));
+    }
+
+    #[test]
+    fn rejects_invalid_selections() {
+        for s in ["", "0", "26", "5-3", "1,,2", "slowest:x", "sometimes"] {
+            assert!(s.parse::<DaySet>().is_err(), "{s}");
+        }
+    }
+}
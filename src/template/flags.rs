@@ -0,0 +1,96 @@
+/// Flags of a solution binary, e.g. `--example` or a day's `--svg <path>`.
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::str::FromStr;
+
+/// The command line of a solution binary.
+pub struct Flags {
+    args: Vec<String>,
+}
+
+impl Flags {
+    pub fn new(args: Vec<String>) -> Self {
+        Self { args }
+    }
+
+    /// The flags the binary was started with.
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().collect())
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.args.iter().any(|a| a == name)
+    }
+
+    /// The value following `name`, e.g. `day09.svg` for `--svg day09.svg`.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .position(|a| a == name)
+            .and_then(|i| self.args.get(i + 1))
+            .map(String::as_str)
+    }
+
+    /// The value following `name`, parsed.
+    pub fn parse<T: FromStr>(&self, name: &str) -> Result<Option<T>, FlagError> {
+        self.value(name)
+            .map(|value| {
+                value.parse().map_err(|_| FlagError {
+                    flag: name.to_string(),
+                    value: value.to_string(),
+                })
+            })
+            .transpose()
+    }
+}
+
+/// An error returned for a flag value that can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagError {
+    flag: String,
+    value: String,
+}
+
+impl Error for FlagError {}
+
+impl Display for FlagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid value `{}` for `{}`", self.value, self.flag)
+    }
+}
+
+/// Writes a debug output of a day, e.g. a drawing, reporting the outcome on stderr.
+pub fn write_debug_output(path: &str, content: &str) {
+    match fs::write(path, content) {
+        Ok(()) => eprintln!("Wrote {path}"),
+        Err(e) => eprintln!("Failed to write {path}: {e}"),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Flags;
+
+    fn flags(args: &[&str]) -> Flags {
+        Flags::new(args.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn reads_flags() {
+        let flags = flags(&["09", "--time", "--svg", "out.svg", "--svg-rejected", "x"]);
+        assert!(flags.has("--time"));
+        assert!(!flags.has("--submit"));
+        assert_eq!(flags.value("--svg"), Some("out.svg"));
+        assert_eq!(flags.parse::<usize>("--other"), Ok(None));
+        assert_eq!(
+            flags
+                .parse::<usize>("--svg-rejected")
+                .unwrap_err()
+                .to_string(),
+            "invalid value `x` for `--svg-rejected`"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+/// Selection of the input a solution binary runs on, from its command line.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::template::Day;
+use crate::template::config::Config;
+use crate::template::flags::{FlagError, Flags};
+
+/// Where the input of a run comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InputSource {
+    /// The puzzle input of the day, the only one answers are submitted for.
+    #[default]
+    Puzzle,
+    /// The example of the day, or the example of one part (e.g. `01-2.txt`).
+    Example {
+        part: Option<u8>,
+    },
+    /// Any other file, e.g. a hand-crafted edge case.
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Reads `--input <path>`, `--example` and `--example-part <part>` from
+    /// the flags.
+    pub fn from_flags(flags: &Flags) -> Result<Self, FlagError> {
+        if let Some(path) = flags.value("--input") {
+            return Ok(Self::from_path(path));
+        }
+        if let Some(part) = flags.parse("--example-part")? {
+            return Ok(Self::Example { part: Some(part) });
+        }
+        if flags.has("--example") {
+            return Ok(Self::Example { part: None });
+        }
+        Ok(Self::Puzzle)
+    }
+
+    /// The input of `--input <path>`, `-` standing for stdin.
+    pub fn from_path(path: &str) -> Self {
+        match path {
+            "-" => Self::Stdin,
+            path => Self::File(path.into()),
+        }
+    }
+
+    /// The arguments selecting this input, for a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::Puzzle => vec![],
+            Self::Example { part: None } => vec!["--example".into()],
+            Self::Example { part: Some(part) } => {
+                vec!["--example-part".into(), part.to_string()]
+            }
+            Self::File(path) => vec!["--input".into(), path.display().to_string()],
+            Self::Stdin => vec!["--input".into(), "-".into()],
+        }
+    }
+
+    pub fn is_puzzle(&self) -> bool {
+        *self == Self::Puzzle
+    }
+
+    /// The file of the input, `None` for stdin.
+    pub fn path(&self, day: Day) -> Option<PathBuf> {
+        let config = Config::get();
+        match self {
+            Self::Puzzle => Some(config.input_path(day)),
+            Self::Example { part: None } => Some(config.example_path(day)),
+            Self::Example { part: Some(part) } => {
+                Some(config.paths.examples.join(format!("{day}-{part}.txt")))
+            }
+            Self::File(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
+    }
+
+    pub fn read(&self, day: Day) -> io::Result<String> {
+        match self.path(day) {
+            Some(path) => fs::read_to_string(path),
+            None => {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text)?;
+                Ok(text)
+            }
+        }
+    }
+
+    /// The answers recorded next to an input file, in a `.answers` file made
+    /// of `<part>: <answer>` lines, e.g. `data/examples/01.answers`.
+    pub fn expected_answers(&self, day: Day) -> HashMap<u8, String> {
+        let Some(path) = self.path(day).filter(|_| !self.is_puzzle()) else {
+            return HashMap::new();
+        };
+        fs::read_to_string(path.with_extension("answers"))
+            .map(|answers| parse_answers(&answers))
+            .unwrap_or_default()
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Puzzle => write!(f, "the puzzle input"),
+            Self::Example { part: None } => write!(f, "the example"),
+            Self::Example { part: Some(part) } => write!(f, "the example of part {part}"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+fn parse_answers(answers: &str) -> HashMap<u8, String> {
+    answers
+        .lines()
+        .filter_map(|l| {
+            let (part, answer) = l.split_once(':')?;
+            Some((part.trim().parse().ok()?, answer.trim().to_string()))
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The input of the running solution binary.
+pub struct Input {
+    pub source: InputSource,
+    pub text: String,
+    pub expected_answers: HashMap<u8, String>,
+}
+
+static INPUT: OnceLock<Input> = OnceLock::new();
+
+/// Reads the input selected on the command line, once per process.
+///
+/// # Panics
+/// Panics if the input file can't be read.
+pub fn read_input(day: Day) -> &'static Input {
+    INPUT.get_or_init(|| {
+        let source = InputSource::from_flags(&Flags::from_env()).unwrap_or_else(|e| panic!("{e}"));
+        let name = source
+            .path(day)
+            .map_or_else(|| "stdin".to_string(), |path| path.display().to_string());
+        let text = source
+            .read(day)
+            .unwrap_or_else(|e| panic!("could not open input file {name}: {e}"));
+        if !source.is_puzzle() {
+            println!("Input: {name}");
+        }
+        Input {
+            expected_answers: source.expected_answers(day),
+            source,
+            text,
+        }
+    })
+}
+
+/// The input read by [`read_input`], if any.
+pub fn current_input() -> Option<&'static Input> {
+    INPUT.get()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{InputSource, parse_answers};
+    use crate::template::flags::Flags;
+
+    fn from_args<S: ToString>(args: &[S]) -> InputSource {
+        let args = args.iter().map(ToString::to_string).collect();
+        InputSource::from_flags(&Flags::new(args)).unwrap()
+    }
+
+    #[test]
+    fn selects_input_from_args() {
+        assert_eq!(from_args(&["01"]), InputSource::Puzzle);
+        let example = from_args(&["01", "--example"]);
+        assert_eq!(example, InputSource::Example { part: None });
+        let part = from_args(&["01", "--time", "--example-part", "2"]);
+        assert_eq!(part, InputSource::Example { part: Some(2) });
+        assert_eq!(from_args(&part.to_args()), part);
+
+        let file = from_args(&["01", "--input", "edge.txt"]);
+        assert_eq!(file, InputSource::File("edge.txt".into()));
+        assert_eq!(from_args(&file.to_args()), file);
+        let stdin = from_args(&["01", "--input", "-"]);
+        assert_eq!(stdin, InputSource::Stdin);
+        assert_eq!(from_args(&stdin.to_args()), stdin);
+    }
+
+    #[test]
+    fn parses_answers() {
+        let answers = parse_answers("1: 142\n2:  281 \nnotes\n");
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&1], "142");
+        assert_eq!(answers[&2], "281");
+    }
+}
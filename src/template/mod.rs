@@ -1,25 +1,29 @@
-use std::{env, fs};
+use std::fs;
+
+use config::Config;
 
 pub mod aoc_cli;
+pub mod cli;
 pub mod commands;
+pub mod config;
+pub mod flags;
+pub mod input;
 pub mod runner;
+pub mod style;
 
 pub use day::*;
+pub use day_set::*;
 
 mod day;
+mod day_set;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
 
-pub const ANSI_ITALIC: &str = "\x1b[3m";
-pub const ANSI_BOLD: &str = "\x1b[1m";
-pub const ANSI_RESET: &str = "\x1b[0m";
-
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = Config::get().data_dir(folder).join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
@@ -27,10 +31,8 @@ pub fn read_file(folder: &str, day: Day) -> String {
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
+    let filepath = Config::get()
+        .data_dir(folder)
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
@@ -50,8 +52,13 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    // `debug` runs once after the parts, e.g. to export a drawing, never while benching.
+    // It is a `fn(&str, &Flags) -> Result<(), Box<dyn Error>>`, whose error is reported.
+    ($day:expr, debug: $debug:expr) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; $debug);
+    };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )* $(; $debug:expr)?) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -61,8 +68,14 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::input::read_input(DAY);
+            $( run_part($func, input.text.as_str(), DAY, $part); )*
+            $(
+                let flags = $crate::template::flags::Flags::from_env();
+                if let Err(e) = $debug(input.text.as_str(), &flags) {
+                    eprintln!("Debug output: {e}");
+                }
+            )?
         }
     };
 }
@@ -2,11 +2,12 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use std::path::Path;
+
 use crate::template::Day;
+use crate::template::config::Config;
 use crate::template::timings::Timings;
 
-static MARKER: &str = "<!--- benchmarking table --->";
-
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
@@ -25,13 +26,19 @@ pub struct TablePosition {
     pos_end: usize,
 }
 
+/// The link to the solution of a day, relative to the readme.
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(config: &Config, day: Day) -> String {
+    let solution = config.solution_path(day);
+    let readme_dir = config.readme.file.parent().unwrap_or(Path::new(""));
+    Path::new(".")
+        .join(solution.strip_prefix(readme_dir).unwrap_or(&solution))
+        .display()
+        .to_string()
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -52,11 +59,12 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
+fn construct_table(config: &Config, prefix: &str, timings: Timings, total_millis: f64) -> String {
+    let marker = config.readme.marker.as_str();
     let header = format!("{prefix} Benchmarks");
 
     let mut lines: Vec<String> = vec![
-        MARKER.into(),
+        marker.into(),
         header,
         String::new(),
         "| Day | Part 1 | Part 2 |".into(),
@@ -64,7 +72,7 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
     ];
 
     for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
+        let path = get_path_for_bin(config, timing.day);
         lines.push(format!(
             "| [Day {}]({}) | `{}` | `{}` |",
             timing.day.into_inner(),
@@ -76,32 +84,45 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
 
     lines.push(String::new());
     lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+    lines.push(marker.into());
 
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+fn update_content(
+    s: &mut String,
+    config: &Config,
+    timings: Timings,
+    total_millis: f64,
+) -> Result<(), Error> {
+    let positions = locate_table(s, &config.readme.marker)?;
+    let table = construct_table(config, "##", timings, total_millis);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
 pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+    update_with(Config::get(), timings)
+}
+
+/// Updates the readme of a configuration, e.g. the one of an archived year.
+pub fn update_with(config: &Config, timings: Timings) -> Result<(), Error> {
+    let path = &config.readme.file;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+    update_content(&mut readme, config, timings, total_millis)?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{MARKER, update_content};
+    use super::{get_path_for_bin, update_content};
+    use crate::template::config::Config;
     use crate::{day, template::timings::Timing, template::timings::Timings};
 
+    const MARKER: &str = "<!--- benchmarking table --->";
+
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
@@ -110,18 +131,21 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    status: Default::default(),
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    status: Default::default(),
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
                     total_nanos: 9e+10,
+                    status: Default::default(),
                 },
             ],
         }
@@ -131,28 +155,28 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &Config::default(), get_mock_timings(), 190.0).unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &Config::default(), get_mock_timings(), 190.0).unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &Config::default(), get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &Config::default(), get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &Config::default(), get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,7 +184,7 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(&mut s, &Config::default(), get_mock_timings(), 190.0).unwrap();
         let expected = [
             "foo",
             "bar",
@@ -180,4 +204,16 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn links_solutions_relative_to_the_readme() {
+        let mut config = Config::default();
+        config.paths.solutions = "years/2024/bin".into();
+        config.readme.file = "years/2024/README.md".into();
+        assert_eq!(get_path_for_bin(&config, day!(3)), "./bin/03.rs");
+        assert_eq!(
+            get_path_for_bin(&Config::default(), day!(3)),
+            "./src/bin/03.rs"
+        );
+    }
 }
@@ -1,10 +1,13 @@
-use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET, Day};
+use crate::template::Day;
+use crate::template::config::Config;
+use crate::template::style::{bold, italic};
 use std::ffi::OsString;
+use std::path::PathBuf;
 use std::{collections::HashSet, io};
 
 use super::{
     all_days,
-    timings::{Timing, Timings},
+    timings::{PartStatus, Timing, Timings},
 };
 
 pub fn run_multi(
@@ -26,7 +29,7 @@ pub fn run_multi(
             }
             need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
+            println!("{}", bold(format_args!("Day {day}")));
             println!("------");
 
             let output =
@@ -40,11 +43,27 @@ pub fn run_multi(
             }
         });
 
+    let failed: Vec<String> = timings
+        .iter()
+        .flat_map(|t| {
+            t.status
+                .iter()
+                .enumerate()
+                .filter(|(_, status)| **status == PartStatus::Failed)
+                .map(move |(i, _)| format!("{}-{}", t.day, i + 1))
+        })
+        .collect();
+    if !failed.is_empty() {
+        println!("\n{} {}", bold("Failed:"), italic(failed.join(", ")));
+    }
+
     if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
-            "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
+            "\n{} {}",
+            bold("Total (Run):"),
+            italic(format_args!("{total_millis:.2}ms"))
         );
         Some(timings)
     } else {
@@ -66,15 +85,18 @@ impl From<std::io::Error> for Error {
 }
 
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(day: Day) -> PathBuf {
+    Config::get().solution_path(day)
 }
 
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{Error, get_path_for_bin};
+    use super::{Error, PartStatus, get_path_for_bin};
     use crate::template::Day;
+    use crate::template::config::Config;
+    use crate::template::runner::{FAILED_MARKER, UNSOLVED_MARKER};
+    use crate::template::style::color_arg;
     use std::ffi::OsString;
     use std::{
         io::{BufRead, BufReader},
@@ -95,8 +117,8 @@ pub mod child_commands {
             return Ok(vec![]);
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let bin_name = Config::get().bin_name(day);
+        let mut args = vec!["run", "--quiet", "--bin", &bin_name];
 
         if is_release {
             args.push("--release");
@@ -107,11 +129,13 @@ pub mod child_commands {
             args.push(arg);
         }
 
+        args.push("--");
         if is_timed || !extra_args.is_empty() {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
+        // the child stdout is piped, pass on whether to use colors.
+        args.push(color_arg());
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
@@ -151,8 +175,15 @@ pub mod child_commands {
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            status: [PartStatus::Unsolved; 2],
         };
 
+        for line in output {
+            if let Some((part, status)) = parse_status(line) {
+                timings.status[part] = status;
+            }
+        }
+
         output
             .iter()
             .filter_map(|l| {
@@ -181,6 +212,28 @@ pub mod child_commands {
         timings
     }
 
+    /// Reads the final state of a `Part N: ...` line, ignoring the intermediate
+    /// result that the runner overwrites with `\r` while benching.
+    fn parse_status(line: &str) -> Option<(usize, PartStatus)> {
+        let line = line.rsplit('\r').next()?;
+        let (part, rest) = line.split_once(": ")?;
+        let part = match part.strip_prefix("Part ")? {
+            "1" => 0,
+            "2" => 1,
+            _ => return None,
+        };
+
+        let status = if rest.starts_with(UNSOLVED_MARKER) {
+            PartStatus::Unsolved
+        } else if rest.starts_with(FAILED_MARKER) {
+            PartStatus::Failed
+        } else {
+            PartStatus::Solved
+        };
+
+        Some((part, status))
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -223,6 +276,7 @@ pub mod child_commands {
     #[cfg(feature = "test_lib")]
     mod tests {
         use super::parse_exec_time;
+        use crate::template::timings::PartStatus;
 
         use crate::day;
 
@@ -269,6 +323,23 @@ pub mod child_commands {
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
+            assert_eq!(res.status, [PartStatus::Unsolved; 2]);
+        }
+
+        #[test]
+        fn parses_failed_parts() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 42 > benching\rPart 1: 42 (1.00ms @ 10 samples)".into(),
+                    "Part 2: ⚠ \u{1b}[3mdisabled\u{1b}[0m\rPart 2: ⚠ \u{1b}[3mdisabled\u{1b}[0m"
+                        .into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 1_000_000_f64);
+            assert_eq!(res.part_2.is_none(), true);
+            assert_eq!(res.status, [PartStatus::Solved, PartStatus::Failed]);
         }
     }
 }
@@ -6,30 +6,97 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
-use crate::template::ANSI_BOLD;
-use crate::template::{ANSI_ITALIC, ANSI_RESET, Day, aoc_cli};
+use crate::template::config::Config;
+use crate::template::input::current_input;
+use crate::template::style::{bold, is_interactive, italic};
+use crate::template::{Day, aoc_cli};
+
+/// Marker printed in place of an answer when a part returned an error.
+pub const FAILED_MARKER: &str = "⚠";
+
+/// Marker printed in place of an answer when a part is not implemented.
+pub const UNSOLVED_MARKER: &str = "✖";
+
+/// What a solution part produced, borrowed from its return value.
+pub enum PartOutcome<'a> {
+    Answer(&'a dyn Display),
+    Error(&'a dyn Display),
+    Unsolved,
+}
+
+/// Return types accepted for solution parts: `Option<T>` signals "no answer",
+/// `Result<T, E>` additionally carries the reason a part failed.
+pub trait PartResult {
+    fn outcome(&self) -> PartOutcome<'_>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    fn outcome(&self) -> PartOutcome<'_> {
+        match self {
+            Some(answer) => PartOutcome::Answer(answer),
+            None => PartOutcome::Unsolved,
+        }
+    }
+}
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    fn outcome(&self) -> PartOutcome<'_> {
+        match self {
+            Ok(answer) => PartOutcome::Answer(answer),
+            Err(err) => PartOutcome::Error(err),
+        }
+    }
+}
+
+pub fn run_part<I: Copy, R: PartResult>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    // the intermediate result is overwritten once benched, on a terminal only.
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        if is_interactive() {
+            print_result(result, &part_str, "");
+        }
+    });
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    let input = current_input();
+    if let Some(expected) = input.and_then(|i| i.expected_answers.get(&part)) {
+        print_expectation(&result, expected);
+    }
+
+    if let PartOutcome::Answer(answer) = result.outcome() {
+        if input.is_none_or(|i| i.source.is_puzzle()) {
+            submit_result(answer, day, part);
+        } else if env::args().any(|a| a == "--submit") {
+            eprintln!(
+                "Not submitting an answer computed from {}.",
+                input.unwrap().source
+            );
+        }
+    }
+}
+
+/// Compares an answer with the one recorded for the input.
+fn print_expectation<R: PartResult>(result: &R, expected: &str) {
+    match result.outcome() {
+        PartOutcome::Answer(answer) if answer.to_string() == expected => {
+            println!("  ✔ {}", italic(format_args!("expected {expected}")));
+        }
+        _ => println!("  ✘ {}", italic(format_args!("expected {expected}"))),
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Copy, T>(
-    func: impl Fn(I) -> T,
+///
+/// Parts that did not produce an answer are never benched.
+fn run_timed<I: Copy, R: PartResult>(
+    func: impl Fn(I) -> R,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    hook: impl Fn(&R),
+) -> (R, Duration, u128) {
     let timer = Instant::now();
     let result = {
         #[cfg(feature = "dhat-heap")]
@@ -41,7 +108,8 @@ fn run_timed<I: Copy, T>(
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let is_answer = matches!(result.outcome(), PartOutcome::Answer(_));
+    let run = if is_answer && std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
         (base_time, 1)
@@ -51,13 +119,14 @@ fn run_timed<I: Copy, T>(
 }
 
 fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
-    let mut stdout = stdout();
-
-    print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
-    let _ = stdout.flush();
+    if is_interactive() {
+        print!(" > {}", italic("benching"));
+        let _ = stdout().flush();
+    }
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    let bench_iterations = (Config::get().defaults.bench_duration.as_nanos()
+        / cmp::max(base_time.as_nanos(), 10))
+    .clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -90,36 +159,52 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+/// Moves back to the start of the line, over the intermediate result.
+fn rewind_line() {
+    if is_interactive() {
+        print!("\r");
+    }
+}
+
+fn print_result<R: PartResult>(result: &R, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
-    match result {
-        Some(result) => {
+    match result.outcome() {
+        PartOutcome::Answer(result) => {
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
-                    print!("\r");
+                    rewind_line();
                     println!("{str}");
                     println!("{result}");
                 }
             } else {
-                let str = format!("{part}: {ANSI_BOLD}{result}{ANSI_RESET}{duration_str}");
+                let str = format!("{part}: {}{duration_str}", bold(result));
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
-                    print!("\r");
+                    rewind_line();
                     println!("{str}");
                 }
             }
         }
-        None => {
+        PartOutcome::Error(err) => {
+            let str = format!("{part}: {FAILED_MARKER} {}", italic(err));
+            if is_intermediate_result {
+                print!("{str}");
+            } else {
+                rewind_line();
+                println!("{str}");
+            }
+        }
+        PartOutcome::Unsolved => {
             if is_intermediate_result {
-                print!("{part}: ✖");
+                print!("{part}: {UNSOLVED_MARKER}");
             } else {
-                print!("\r");
-                println!("{part}: ✖             ");
+                rewind_line();
+                println!("{part}: {UNSOLVED_MARKER}             ");
             }
         }
     }
@@ -128,8 +213,8 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
+fn submit_result(
+    result: &dyn Display,
     day: Day,
     part: u8,
 ) -> Option<Result<Output, aoc_cli::AocCommandError>> {
@@ -0,0 +1,175 @@
+/// Styling of the terminal output. ANSI escape codes are only written when
+/// colors are enabled, and lines are only updated in place on a terminal.
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{IsTerminal, stdout};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+const ANSI_ITALIC: &str = "\x1b[3m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// The value of `--color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Colors when stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Reads `--color <when>` or `--color=<when>` from the arguments, up to a `--`.
+    pub fn from_args(args: &[String]) -> Result<Self, ColorChoiceError> {
+        let mut args = args.iter().take_while(|a| *a != "--");
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--color=") {
+                return value.parse();
+            }
+            if arg == "--color" {
+                return args.next().map_or(Err(ColorChoiceError), |v| v.parse());
+            }
+        }
+        Ok(Self::Auto)
+    }
+
+    fn colors(self, no_color: bool, is_terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => !no_color && is_terminal,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = ColorChoiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(ColorChoiceError),
+        }
+    }
+}
+
+/// An error returned for a `--color` value other than `auto`, `always` or `never`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorChoiceError;
+
+impl Error for ColorChoiceError {}
+
+impl Display for ColorChoiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting `--color` to be one of `auto`, `always` or `never`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+struct Style {
+    colors: bool,
+    interactive: bool,
+}
+
+fn style() -> &'static Style {
+    static STYLE: OnceLock<Style> = OnceLock::new();
+    STYLE.get_or_init(|| {
+        let args: Vec<String> = env::args().collect();
+        let is_terminal = stdout().is_terminal();
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Style {
+            colors: ColorChoice::from_args(&args)
+                .unwrap_or_default()
+                .colors(no_color, is_terminal),
+            interactive: is_terminal,
+        }
+    })
+}
+
+/// Whether stdout is a terminal, on which lines can be updated in place.
+pub fn is_interactive() -> bool {
+    style().interactive
+}
+
+/// The `--color` argument passing the color decision on to a solution binary,
+/// whose stdout may be piped.
+pub fn color_arg() -> &'static str {
+    if style().colors {
+        "--color=always"
+    } else {
+        "--color=never"
+    }
+}
+
+/// Text displayed with an ANSI style when colors are enabled.
+pub struct Styled<T> {
+    code: &'static str,
+    content: T,
+}
+
+impl<T: Display> Display for Styled<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if style().colors {
+            write!(f, "{}{}{ANSI_RESET}", self.code, self.content)
+        } else {
+            self.content.fmt(f)
+        }
+    }
+}
+
+pub fn bold<T: Display>(content: T) -> Styled<T> {
+    Styled {
+        code: ANSI_BOLD,
+        content,
+    }
+}
+
+pub fn italic<T: Display>(content: T) -> Styled<T> {
+    Styled {
+        code: ANSI_ITALIC,
+        content,
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::ColorChoice;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn reads_color_choice_from_args() {
+        let choice = |a: &[&str]| ColorChoice::from_args(&args(a));
+        assert_eq!(choice(&["01", "--time"]), Ok(ColorChoice::Auto));
+        assert_eq!(choice(&["01", "--color=never"]), Ok(ColorChoice::Never));
+        assert_eq!(
+            choice(&["all", "--color", "always"]),
+            Ok(ColorChoice::Always)
+        );
+        assert_eq!(
+            choice(&["all", "--", "--color=never"]),
+            Ok(ColorChoice::Auto)
+        );
+        assert!(choice(&["all", "--color=sometimes"]).is_err());
+        assert!(choice(&["all", "--color"]).is_err());
+    }
+
+    #[test]
+    fn resolves_colors() {
+        assert!(ColorChoice::Auto.colors(false, true));
+        assert!(!ColorChoice::Auto.colors(true, true));
+        assert!(!ColorChoice::Auto.colors(false, false));
+        assert!(ColorChoice::Always.colors(true, false));
+        assert!(!ColorChoice::Never.colors(false, true));
+    }
+}
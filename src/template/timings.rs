@@ -1,9 +1,18 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fs, io::Error, path::Path, str::FromStr};
 use tinyjson::JsonValue;
 
 use crate::template::Day;
-
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+use crate::template::config::Config;
+
+/// How a solution part ended, as reported by the runner.
+/// Not persisted: stored timings only carry benchmark results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PartStatus {
+    #[default]
+    Unsolved,
+    Solved,
+    Failed,
+}
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -12,6 +21,7 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    pub status: [PartStatus; 2],
 }
 
 /// Represents benchmark times for a set of days.
@@ -25,13 +35,18 @@ impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(&Config::get().paths.timings)?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        Self::read_from(&Config::get().paths.timings)
+    }
+
+    /// Rehydrate timings from the JSON file at `path`, e.g. the one of an archived year.
+    pub fn read_from(path: &Path) -> Self {
+        fs::read_to_string(path)
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
@@ -51,13 +66,13 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|t| t.day);
         Timings { data }
     }
 
     /// Sum up total duration of timings as millis.
     pub fn total_millis(&self) -> f64 {
-        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
+        self.data.iter().fold(0.0, |sum, x| sum + x.total_nanos) / 1_000_000_f64
     }
 
     pub fn is_day_complete(&self, day: Day) -> bool {
@@ -166,11 +181,21 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        // benched parts were solved; anything else is unknown once stored.
+        let status = [part_1, part_2].map(|p| {
+            if p.is_some() {
+                PartStatus::Solved
+            } else {
+                PartStatus::Unsolved
+            }
+        });
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            status,
         })
     }
 }
@@ -191,18 +216,21 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    status: Default::default(),
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    status: Default::default(),
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    status: Default::default(),
                 },
             ],
         }
@@ -282,10 +310,11 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    status: Default::default(),
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -296,10 +325,11 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    status: Default::default(),
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -310,10 +340,11 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    status: Default::default(),
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
@@ -334,6 +365,7 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    status: Default::default(),
                 }],
             };
             let merged = timings.merge(&other);
@@ -354,6 +386,7 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    status: Default::default(),
                 }],
             };
             let merged = timings.merge(&other);
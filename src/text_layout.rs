@@ -0,0 +1,116 @@
+/// Helpers for puzzles laid out as blocks of text, e.g. numbers written in columns.
+///
+/// Positions are byte offsets: layouts are expected to be ASCII.
+use std::ops::Range;
+
+/// A block of text lines, seen as a grid where short lines are padded with spaces.
+#[derive(Debug, Clone)]
+pub struct TextBlock<'a> {
+    lines: Vec<&'a str>,
+    width: usize,
+}
+
+impl<'a> TextBlock<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        Self { lines, width }
+    }
+
+    pub fn lines(&self) -> &[&'a str] {
+        &self.lines
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    fn is_blank_column(&self, col: usize) -> bool {
+        self.lines
+            .iter()
+            .all(|l| l.as_bytes().get(col).is_none_or(u8::is_ascii_whitespace))
+    }
+
+    /// Splits the block into groups of columns separated by all-blank columns.
+    pub fn column_groups(&self) -> Vec<ColumnGroup<'_, 'a>> {
+        let mut groups = Vec::new();
+        let mut start = None;
+        for col in 0..=self.width {
+            match (start, col == self.width || self.is_blank_column(col)) {
+                (None, false) => start = Some(col),
+                (Some(s), true) => {
+                    groups.push(ColumnGroup {
+                        block: self,
+                        span: s..col,
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        groups
+    }
+}
+
+/// A range of columns of a [`TextBlock`].
+#[derive(Debug, Clone)]
+pub struct ColumnGroup<'b, 'a> {
+    block: &'b TextBlock<'a>,
+    span: Range<usize>,
+}
+
+impl<'a> ColumnGroup<'_, 'a> {
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The part of `line` covered by this group, clipped to the line length.
+    pub fn slice<'l>(&self, line: &'l str) -> &'l str {
+        let end = self.span.end.min(line.len());
+        line.get(self.span.start.min(end)..end).unwrap_or("")
+    }
+
+    /// The group read row-wise: its slice of every line, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.block.lines.iter().map(|l| self.slice(l))
+    }
+
+    /// The group read column-wise: every column, left to right, as the string
+    /// of its characters from top to bottom.
+    pub fn columns(&self) -> impl Iterator<Item = String> + '_ {
+        self.span.clone().map(|col| {
+            self.block
+                .lines
+                .iter()
+                .map(|l| l.as_bytes().get(col).map_or(' ', |b| *b as char))
+                .collect()
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::TextBlock;
+
+    #[test]
+    fn splits_column_groups() {
+        let block = TextBlock::new("12  3\n4   56\n 7");
+        let groups = block.column_groups();
+        assert_eq!(
+            groups.iter().map(|g| g.span()).collect::<Vec<_>>(),
+            vec![0..2, 4..6]
+        );
+    }
+
+    #[test]
+    fn reads_rows_and_columns() {
+        let block = TextBlock::new("12 3\n 4 56\n5");
+        let groups = block.column_groups();
+        assert_eq!(groups[0].rows().collect::<Vec<_>>(), vec!["12", " 4", "5"]);
+        assert_eq!(groups[0].columns().collect::<Vec<_>>(), vec!["1 5", "24 "]);
+        assert_eq!(groups[1].rows().collect::<Vec<_>>(), vec!["3", "56", ""]);
+        assert_eq!(groups[1].columns().collect::<Vec<_>>(), vec!["35 ", " 6 "]);
+    }
+}